@@ -0,0 +1,58 @@
+use std::env;
+use std::path::PathBuf;
+
+pub const TILESET_FILE_NAME: &str = "taffer.png";
+
+/// Bundled copy of the tileset, so the game can always start even when no asset directory is found.
+pub const FALLBACK_TILESET: &[u8] = include_bytes!("../res/taffer.png");
+
+/// Points at a directory holding the game's assets, taking precedence over every other location.
+const ASSETS_DIR_ENV_VAR: &str = "SIM_ASSETS_DIR";
+
+/// Directories we look for assets in, in order of preference.
+fn asset_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+
+    if let Some(dir) = env::var_os(ASSETS_DIR_ENV_VAR) {
+        dirs.push(PathBuf::from(dir));
+    }
+
+    if let Some(exe_dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+    {
+        dirs.push(exe_dir.join("res"));
+    }
+
+    if let Some(data_dir) = data_dir() {
+        dirs.push(data_dir.join("res"));
+    }
+
+    // Running through cargo from the repository root.
+    dirs.push(PathBuf::from("res"));
+
+    dirs
+}
+
+/// The platform's per-user data directory for the game, e.g. ~/.local/share/sim on Linux.
+fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+
+    base.map(|base| base.join("sim"))
+}
+
+/// Finds the first existing copy of an asset across the asset directories.
+pub fn find_asset(file_name: &str) -> Option<PathBuf> {
+    asset_dirs()
+        .into_iter()
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use crate::location::Point;
+use crate::render::Viewport;
+
+/// How long camera moves take when no `--camera-ease-ms` is given.
+pub const DEFAULT_EASE_DURATION: Duration = Duration::from_millis(300);
+
+struct Transition {
+    start: Instant,
+    from_anchor: Point,
+    from_zoom: f64,
+    to_anchor: Point,
+    to_zoom: f64,
+    /// Where the camera last put the viewport. When the viewport is anywhere else the player moved
+    /// it themselves and the transition is abandoned.
+    last_anchor: Point,
+    last_zoom: f64,
+}
+
+/// Moves the viewport to a new position and zoom gradually, so jumps don't lose the player's sense
+/// of where they are.
+pub struct Camera {
+    ease_duration: Duration,
+    transition: Option<Transition>,
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+impl Camera {
+    /// A zero `ease_duration` makes every move instant.
+    pub fn new(ease_duration: Duration) -> Self {
+        Self {
+            ease_duration,
+            transition: None,
+        }
+    }
+
+    pub fn move_to(&mut self, viewport: &mut Viewport, anchor: Point, zoom: f64) {
+        if self.ease_duration.is_zero() {
+            viewport.anchor = anchor;
+            viewport.zoom = zoom;
+            return;
+        }
+
+        self.transition = Some(Transition {
+            start: Instant::now(),
+            from_anchor: viewport.anchor,
+            from_zoom: viewport.zoom,
+            to_anchor: anchor,
+            to_zoom: zoom,
+            last_anchor: viewport.anchor,
+            last_zoom: viewport.zoom,
+        });
+    }
+
+    /// Moves the viewport so the given universe coordinate ends up in the middle.
+    pub fn center_on(&mut self, viewport: &mut Viewport, x: i32, y: i32) {
+        let anchor = viewport.centered_anchor(x, y);
+        let zoom = viewport.zoom;
+        self.move_to(viewport, anchor, zoom);
+    }
+
+    /// Advances a transition in progress. Runs every loop, before anything reads the viewport.
+    pub fn update(&mut self, viewport: &mut Viewport) {
+        let Some(transition) = self.transition.as_mut() else {
+            return;
+        };
+
+        if viewport.anchor != transition.last_anchor || viewport.zoom != transition.last_zoom {
+            self.transition = None;
+            return;
+        }
+
+        let progress =
+            (transition.start.elapsed().as_secs_f64() / self.ease_duration.as_secs_f64()).min(1.0);
+        // Ease out, fast at first and settling gently on the target.
+        let t = 1.0 - (1.0 - progress).powi(3);
+
+        viewport.anchor = Point {
+            x: lerp(
+                transition.from_anchor.x as f64,
+                transition.to_anchor.x as f64,
+                t,
+            )
+            .round() as i32,
+            y: lerp(
+                transition.from_anchor.y as f64,
+                transition.to_anchor.y as f64,
+                t,
+            )
+            .round() as i32,
+        };
+        viewport.zoom = lerp(transition.from_zoom, transition.to_zoom, t);
+        transition.last_anchor = viewport.anchor;
+        transition.last_zoom = viewport.zoom;
+
+        if progress >= 1.0 {
+            self.transition = None;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+
+use crate::entity::{EntityId, EntityType};
+use crate::render::Viewport;
+use crate::text_field::TextField;
+use crate::world::World;
+
+/// How many lines of output the console keeps around and shows.
+pub const MAX_OUTPUT_LINES: usize = 8;
+
+/// Leaves room for the prompt and cursor on a 64 tile wide line.
+const MAX_INPUT_LENGTH: usize = 60;
+
+const HELP_TEXT: [&str; 4] = [
+    "spawn <star|planet|moon|asteroid> <x> <y>",
+    "despawn <id>",
+    "tp <x> <y>",
+    "help",
+];
+
+enum Command {
+    Despawn(EntityId),
+    Help,
+    Spawn {
+        entity_type: EntityType,
+        x: i32,
+        y: i32,
+    },
+    Teleport {
+        x: i32,
+        y: i32,
+    },
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<&str>, name: &str) -> Result<T, String> {
+    let arg = arg.ok_or_else(|| format!("missing <{name}>"))?;
+    arg.parse().map_err(|_| format!("invalid <{name}> '{arg}'"))
+}
+
+/// Coordinates typed into the console have to lie within this distance of the origin. Anything near
+/// the limits of `i32` overflows once the viewport or minimap does math on it.
+const MAX_COORDINATE: i32 = 1_000_000;
+
+fn parse_coordinate(arg: Option<&str>, name: &str) -> Result<i32, String> {
+    let coordinate: i32 = parse_arg(arg, name)?;
+    if !(-MAX_COORDINATE..=MAX_COORDINATE).contains(&coordinate) {
+        return Err(format!("<{name}> must be within {MAX_COORDINATE} of 0"));
+    }
+    Ok(coordinate)
+}
+
+fn parse_command(input: &str) -> Result<Command, String> {
+    let mut words = input.split_whitespace();
+
+    match words.next() {
+        Some("despawn") => Ok(Command::Despawn(parse_arg(words.next(), "id")?)),
+        Some("help") => Ok(Command::Help),
+        Some("spawn") => Ok(Command::Spawn {
+            entity_type: words
+                .next()
+                .ok_or_else(|| "missing entity type".to_string())?
+                .parse()?,
+            x: parse_coordinate(words.next(), "x")?,
+            y: parse_coordinate(words.next(), "y")?,
+        }),
+        Some("tp") => Ok(Command::Teleport {
+            x: parse_coordinate(words.next(), "x")?,
+            y: parse_coordinate(words.next(), "y")?,
+        }),
+        Some(other) => Err(format!("unknown command '{other}', try help")),
+        None => Err("empty command".to_string()),
+    }
+}
+
+/// A developer console, toggled with backtick, that applies text commands directly to the world.
+pub struct Console {
+    pub open: bool,
+    pub input: TextField,
+    pub output: VecDeque<String>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            open: false,
+            input: TextField::new(MAX_INPUT_LENGTH, '`'),
+            output: VecDeque::new(),
+        }
+    }
+}
+
+impl Console {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    fn print(&mut self, line: impl Into<String>) {
+        if self.output.len() == MAX_OUTPUT_LINES {
+            self.output.pop_front();
+        }
+        self.output.push_back(line.into());
+    }
+
+    /// Runs the current input as a command and clears it.
+    pub fn submit(&mut self, world: &mut World, viewport: &mut Viewport) {
+        let input = self.input.take();
+        self.print(format!("> {input}"));
+
+        match parse_command(&input) {
+            Ok(command) => self.execute(command, world, viewport),
+            Err(err) => self.print(err),
+        }
+    }
+
+    fn execute(&mut self, command: Command, world: &mut World, viewport: &mut Viewport) {
+        match command {
+            Command::Despawn(entity_id) => {
+                if world.entity_types.contains_key(&entity_id) {
+                    world.despawn(entity_id);
+                    self.print(format!("despawned {entity_id}"));
+                } else {
+                    self.print(format!("no entity with id {entity_id}"));
+                }
+            }
+            Command::Help => {
+                for line in HELP_TEXT {
+                    self.print(line);
+                }
+            }
+            Command::Spawn { entity_type, x, y } => {
+                let entity_id = world.spawn(entity_type, x, y);
+                self.print(format!("spawned {entity_id} at {x} {y}"));
+            }
+            Command::Teleport { x, y } => {
+                viewport.center_on_entity(x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use sdl2::mouse::{Cursor, SystemCursor};
+use tracing::warn;
+
+/// What the mouse is currently pointing at, which decides the cursor shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorContext {
+    EmptySpace,
+    Entity,
+}
+
+struct SystemCursors {
+    crosshair: Cursor,
+    hand: Cursor,
+}
+
+pub struct Cursors {
+    /// Some video drivers, like SDL's dummy and offscreen drivers, have no system cursors. Without
+    /// them we keep the default cursor, the shape is only a hint.
+    system_cursors: Option<SystemCursors>,
+    context: Option<CursorContext>,
+}
+
+impl Cursors {
+    pub fn new() -> Self {
+        let system_cursors = Cursor::from_system(SystemCursor::Crosshair)
+            .and_then(|crosshair| {
+                Ok(SystemCursors {
+                    crosshair,
+                    hand: Cursor::from_system(SystemCursor::Hand)?,
+                })
+            })
+            .inspect_err(|err| warn!(%err, "failed to create mouse cursors, using the default"))
+            .ok();
+
+        Self {
+            system_cursors,
+            context: None,
+        }
+    }
+
+    /// Switches the cursor shape, only calling into SDL when the context changes.
+    pub fn set_context(&mut self, context: CursorContext) {
+        let Some(system_cursors) = &self.system_cursors else {
+            return;
+        };
+        if self.context == Some(context) {
+            return;
+        }
+
+        match context {
+            CursorContext::EmptySpace => system_cursors.crosshair.set(),
+            CursorContext::Entity => system_cursors.hand.set(),
+        }
+
+        self.context = Some(context);
+    }
+}
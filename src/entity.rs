@@ -1,26 +1,134 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 use crate::location::Point;
 
 pub type EntityId = u32;
 
 pub enum EntityType {
+    Asteroid,
     Moon,
     Planet,
     Space,
     Star,
 }
 
+impl FromStr for EntityType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asteroid" => Ok(EntityType::Asteroid),
+            "moon" => Ok(EntityType::Moon),
+            "planet" => Ok(EntityType::Planet),
+            "star" => Ok(EntityType::Star),
+            other => Err(format!("unknown entity type '{other}'")),
+        }
+    }
+}
+
+impl fmt::Display for EntityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EntityType::Asteroid => "Asteroid",
+            EntityType::Moon => "Moon",
+            EntityType::Planet => "Planet",
+            EntityType::Space => "Space",
+            EntityType::Star => "Star",
+        };
+        write!(f, "{name}")
+    }
+}
+
 pub type EntityTypeMap = HashMap<EntityId, EntityType>;
 
+/// Spectral class of a star, from the hottest and largest to the coolest and smallest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarClass {
+    O,
+    B,
+    A,
+    F,
+    G,
+    K,
+    M,
+}
+
+impl StarClass {
+    pub const ALL: [StarClass; 7] = [
+        StarClass::O,
+        StarClass::B,
+        StarClass::A,
+        StarClass::F,
+        StarClass::G,
+        StarClass::K,
+        StarClass::M,
+    ];
+
+    /// How often each class comes up when generating stars, in the same order as `ALL`. Cool,
+    /// small stars are the most common, as they are in reality, though far less dominant.
+    pub const WEIGHTS: [u32; 7] = [1, 2, 4, 8, 12, 16, 24];
+
+    /// Whether stars of this class are large enough to be drawn with a bigger glyph.
+    pub fn is_giant(&self) -> bool {
+        matches!(self, StarClass::O | StarClass::B | StarClass::A)
+    }
+}
+
+impl fmt::Display for StarClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 pub trait Orbital {
     fn update_position(&mut self, anchor_x: i32, anchor_y: i32, time_delta: f64);
 }
 
+/// The shape of an orbit. The anchor sits at one focus of the ellipse, a circle is the case where
+/// both axes are equal.
+#[derive(Debug, Clone, Copy)]
+pub struct Orbit {
+    pub semi_major_axis: f64,
+    pub semi_minor_axis: f64,
+    /// Rotation of the ellipse, the angle from the x axis to the point closest to the anchor.
+    pub argument_of_periapsis: f64,
+}
+
+impl Orbit {
+    pub fn circular(radius: f64) -> Self {
+        Self {
+            semi_major_axis: radius,
+            semi_minor_axis: radius,
+            argument_of_periapsis: 0.0,
+        }
+    }
+
+    pub fn elliptical(semi_major_axis: f64, eccentricity: f64, argument_of_periapsis: f64) -> Self {
+        Self {
+            semi_major_axis,
+            semi_minor_axis: semi_major_axis * (1.0 - eccentricity.powi(2)).sqrt(),
+            argument_of_periapsis,
+        }
+    }
+
+    /// Offset from the anchor at the given angle along the orbit.
+    pub fn offset(&self, angle: f64) -> (f64, f64) {
+        // Distance from the center of the ellipse to the focus the anchor sits at.
+        let focal_distance = (self.semi_major_axis.powi(2) - self.semi_minor_axis.powi(2)).sqrt();
+        let x = self.semi_major_axis * angle.cos() - focal_distance;
+        let y = self.semi_minor_axis * angle.sin();
+
+        let (sin, cos) = self.argument_of_periapsis.sin_cos();
+        (x * cos - y * sin, x * sin + y * cos)
+    }
+}
+
 pub struct OrbitalEntity {
     pub id: EntityId,
     pub anchor_id: EntityId,
-    pub radius: f64,
+    pub orbit: Orbit,
     pub angle: f64,
     pub angular_velocity: f64, // radians per second
     pub position: Point,
@@ -29,7 +137,8 @@ pub struct OrbitalEntity {
 impl Orbital for OrbitalEntity {
     fn update_position(&mut self, anchor_x: i32, anchor_y: i32, time_delta: f64) {
         self.angle += self.angular_velocity * time_delta;
-        self.position.x = anchor_x + (self.radius * self.angle.cos()) as i32;
-        self.position.y = anchor_y + (self.radius * self.angle.sin()) as i32;
+        let (x, y) = self.orbit.offset(self.angle);
+        self.position.x = anchor_x + x as i32;
+        self.position.y = anchor_y + y as i32;
     }
 }
@@ -0,0 +1,10 @@
+/// Formats a quantity for display, scaling large values down to a short suffixed form, e.g. 1.2k,
+/// 3.4M, 5.6B. Values below a thousand are shown as whole numbers.
+pub fn format_quantity(value: f64) -> String {
+    match value.abs() {
+        num if num >= 999_950_000.0 => format!("{:.1}B", value / 1_000_000_000.0),
+        num if num >= 999_950.0 => format!("{:.1}M", value / 1_000_000.0),
+        num if num >= 999.5 => format!("{:.1}k", value / 1_000.0),
+        _ => format!("{}", value.round() as i64),
+    }
+}
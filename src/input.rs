@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+
+use crate::location::Point;
+use crate::render::Viewport;
+
+/// How much a single zoom step, from the keyboard or the mouse wheel, changes the zoom level.
+pub const ZOOM_STEP: f64 = 1.0;
+
+/// Where keyboard zoom keeps the view in place. Mouse wheel zoom always zooms at the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoomAnchor {
+    Cursor,
+    Center,
+}
+
+impl FromStr for ZoomAnchor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cursor" => Ok(ZoomAnchor::Cursor),
+            "center" => Ok(ZoomAnchor::Center),
+            other => Err(format!("unknown zoom anchor '{other}'")),
+        }
+    }
+}
+
+impl ZoomAnchor {
+    /// The pixel position to zoom at. Zooming at the cursor falls back to the center of the window
+    /// while the mouse is outside of it, its last known position is stale by then.
+    pub fn point(&self, mouse: Option<(i32, i32)>, width: u32, height: u32) -> (i32, i32) {
+        let center = (width as i32 / 2, height as i32 / 2);
+        match self {
+            ZoomAnchor::Cursor => mouse.unwrap_or(center),
+            ZoomAnchor::Center => center,
+        }
+    }
+}
+
+/// Mouse buttons that pan the viewport when dragged.
+pub const PAN_BUTTONS: [MouseButton; 2] = [MouseButton::Right, MouseButton::Middle];
+
+/// How far the mouse has to travel with a pan button held before we treat it as a drag rather than
+/// a click. Keeps a slightly shaky click from nudging the view, and leaves clicks free for orders.
+const DRAG_THRESHOLD_PIXELS: i32 = 4;
+
+struct DragStart {
+    button: MouseButton,
+    mouse: Point,
+    anchor: Point,
+    dragging: bool,
+}
+
+#[derive(Default)]
+pub struct DragPan {
+    start: Option<DragStart>,
+}
+
+impl DragPan {
+    pub fn begin(&mut self, button: MouseButton, mouse_x: i32, mouse_y: i32, anchor: Point) {
+        if !PAN_BUTTONS.contains(&button) || self.start.is_some() {
+            return;
+        }
+
+        self.start = Some(DragStart {
+            button,
+            mouse: Point {
+                x: mouse_x,
+                y: mouse_y,
+            },
+            anchor,
+            dragging: false,
+        });
+    }
+
+    /// Returns the new viewport anchor if the mouse has been dragged far enough to pan.
+    pub fn update(&mut self, mouse_x: i32, mouse_y: i32, tile_pixel_width: u32) -> Option<Point> {
+        let start = self.start.as_mut()?;
+        let dx = mouse_x - start.mouse.x;
+        let dy = mouse_y - start.mouse.y;
+
+        if !start.dragging && dx.abs().max(dy.abs()) < DRAG_THRESHOLD_PIXELS {
+            return None;
+        }
+        start.dragging = true;
+
+        // Dragging moves the universe with the mouse, so the anchor moves the opposite way.
+        Some(Point {
+            x: start.anchor.x - dx / tile_pixel_width as i32,
+            y: start.anchor.y - dy / tile_pixel_width as i32,
+        })
+    }
+
+    pub fn end(&mut self, button: MouseButton) {
+        if self
+            .start
+            .as_ref()
+            .is_some_and(|start| start.button == button)
+        {
+            self.start = None;
+        }
+    }
+}
+
+/// A distance measurement between two universe coordinates, made by holding M and dragging.
+pub struct Measurement {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Measurement {
+    pub fn distance(&self) -> f64 {
+        let dx = (self.end.x - self.start.x) as f64;
+        let dy = (self.end.y - self.start.y) as f64;
+        dx.hypot(dy)
+    }
+}
+
+/// Keys that recall a camera bookmark, or save one while ctrl is held. One bookmark per key.
+pub const BOOKMARK_KEYS: [Keycode; 4] = [Keycode::F5, Keycode::F6, Keycode::F7, Keycode::F8];
+
+#[derive(Clone, Copy)]
+pub struct CameraBookmark {
+    pub anchor: Point,
+    pub zoom: f64,
+}
+
+/// Saved camera positions and zoom levels, so the player can jump between places of interest.
+#[derive(Default)]
+pub struct CameraBookmarks {
+    slots: [Option<CameraBookmark>; BOOKMARK_KEYS.len()],
+}
+
+impl CameraBookmarks {
+    /// Returns the bookmark slot belonging to `keycode`, if any.
+    pub fn slot(keycode: Keycode) -> Option<usize> {
+        BOOKMARK_KEYS.iter().position(|&key| key == keycode)
+    }
+
+    pub fn is_set(&self, slot: usize) -> bool {
+        self.slots[slot].is_some()
+    }
+
+    pub fn get(&self, slot: usize) -> Option<CameraBookmark> {
+        self.slots[slot]
+    }
+
+    pub fn save(&mut self, slot: usize, viewport: &Viewport) {
+        self.slots[slot] = Some(CameraBookmark {
+            anchor: viewport.anchor,
+            zoom: viewport.zoom,
+        });
+    }
+}
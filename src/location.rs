@@ -1,26 +1,137 @@
-use std::{
-    collections::HashMap,
-    ops::{Deref, DerefMut},
-};
+use std::{collections::HashMap, ops::Deref};
 
 use crate::{entity::EntityId, Viewport};
 
-#[derive(Debug, Default, Clone, Copy)]
+/// Size in universe coordinates of a single cell in the location map's spatial hash.
+const CELL_SIZE: i32 = 8;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Point {
     pub x: i32,
     pub y: i32,
 }
 
+impl Point {
+    fn cell(&self) -> (i32, i32) {
+        (self.x.div_euclid(CELL_SIZE), self.y.div_euclid(CELL_SIZE))
+    }
+
+    fn distance_squared(&self, other: &Point) -> i64 {
+        let dx = (self.x - other.x) as i64;
+        let dy = (self.y - other.y) as i64;
+        dx * dx + dy * dy
+    }
+}
+
+/// Where every entity is. Besides the plain map from entity to location we keep a spatial hash, a
+/// grid of cells each listing the entities inside, so area queries only look at nearby entities.
+/// All writes go through `add_entity` and `remove_entity` to keep the two in sync.
 #[derive(Debug)]
-pub struct LocationMap(HashMap<EntityId, Point>);
+pub struct LocationMap {
+    locations: HashMap<EntityId, Point>,
+    /// Locations as they were before the last simulation unit, used to interpolate when rendering
+    /// between simulation units.
+    previous_locations: HashMap<EntityId, Point>,
+    cells: HashMap<(i32, i32), Vec<EntityId>>,
+}
 
 impl LocationMap {
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self {
+            locations: HashMap::new(),
+            previous_locations: HashMap::new(),
+            cells: HashMap::new(),
+        }
     }
 
+    /// Adds the entity at the given location, or moves it there if it already has one.
     pub fn add_entity(&mut self, entity_id: EntityId, x: i32, y: i32) {
-        self.0.insert(entity_id, Point { x, y });
+        let point = Point { x, y };
+
+        if let Some(previous) = self.locations.insert(entity_id, point) {
+            if previous.cell() == point.cell() {
+                return;
+            }
+            self.remove_from_cell(entity_id, &previous);
+        }
+
+        self.cells.entry(point.cell()).or_default().push(entity_id);
+    }
+
+    pub fn remove_entity(&mut self, entity_id: EntityId) {
+        self.previous_locations.remove(&entity_id);
+        if let Some(previous) = self.locations.remove(&entity_id) {
+            self.remove_from_cell(entity_id, &previous);
+        }
+    }
+
+    fn remove_from_cell(&mut self, entity_id: EntityId, point: &Point) {
+        let cell = point.cell();
+        if let Some(entity_ids) = self.cells.get_mut(&cell) {
+            entity_ids.retain(|id| *id != entity_id);
+            if entity_ids.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Returns the entities within the rectangle spanned by `min` and `max`, inclusive.
+    pub fn entities_in_rect<'a>(
+        &'a self,
+        min: &Point,
+        max: &Point,
+    ) -> impl Iterator<Item = (EntityId, Point)> + 'a {
+        let (min_cell_x, min_cell_y) = min.cell();
+        let (max_cell_x, max_cell_y) = max.cell();
+        let (min, max) = (*min, *max);
+
+        (min_cell_x..=max_cell_x)
+            .flat_map(move |cell_x| (min_cell_y..=max_cell_y).map(move |cell_y| (cell_x, cell_y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|entity_id| (*entity_id, self.locations[entity_id]))
+            .filter(move |(_, point)| {
+                point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y
+            })
+    }
+
+    /// Returns the entity closest to `point`, as long as it is no further than `max_distance` away
+    /// along either axis.
+    pub fn nearest_entity(&self, point: &Point, max_distance: i32) -> Option<EntityId> {
+        let min = Point {
+            x: point.x - max_distance,
+            y: point.y - max_distance,
+        };
+        let max = Point {
+            x: point.x + max_distance,
+            y: point.y + max_distance,
+        };
+
+        self.entities_in_rect(&min, &max)
+            .min_by_key(|(entity_id, location)| (location.distance_squared(point), *entity_id))
+            .map(|(entity_id, _)| entity_id)
+    }
+
+    /// How many cells of the spatial hash hold at least one entity.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Call before each simulation unit, so rendering can interpolate from where entities were.
+    pub fn remember_previous_locations(&mut self) {
+        self.previous_locations.clone_from(&self.locations);
+    }
+
+    /// Where the entity appears to be `alpha` of the way from its previous location to its current
+    /// one. Entities added since the last simulation unit appear at their current location.
+    pub fn interpolated_location(&self, entity_id: EntityId, alpha: f64) -> Option<(f64, f64)> {
+        let current = self.locations.get(&entity_id)?;
+        let previous = self.previous_locations.get(&entity_id).unwrap_or(current);
+
+        Some((
+            previous.x as f64 + (current.x - previous.x) as f64 * alpha,
+            previous.y as f64 + (current.y - previous.y) as f64 * alpha,
+        ))
     }
 
     // We render the viewport, the anchor is the top left corner of the viewport. So we need to
@@ -37,12 +148,84 @@ impl Deref for LocationMap {
     type Target = HashMap<EntityId, Point>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.locations
     }
 }
 
-impl DerefMut for LocationMap {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids_in_rect(location_map: &LocationMap, min: Point, max: Point) -> Vec<EntityId> {
+        let mut entity_ids: Vec<EntityId> = location_map
+            .entities_in_rect(&min, &max)
+            .map(|(entity_id, _)| entity_id)
+            .collect();
+        entity_ids.sort();
+        entity_ids
+    }
+
+    #[test]
+    fn moving_entity_changes_cell() {
+        let mut location_map = LocationMap::new();
+        location_map.add_entity(1, 0, 0);
+        location_map.add_entity(1, 20, 0);
+
+        assert_eq!(location_map.cells.get(&(0, 0)), None);
+        assert_eq!(location_map.cells[&(2, 0)], vec![1]);
+        assert_eq!(location_map[&1], Point { x: 20, y: 0 });
+    }
+
+    #[test]
+    fn moving_within_cell_keeps_single_entry() {
+        let mut location_map = LocationMap::new();
+        location_map.add_entity(1, 0, 0);
+        location_map.add_entity(1, 3, 3);
+
+        assert_eq!(location_map.cells[&(0, 0)], vec![1]);
+    }
+
+    #[test]
+    fn removing_last_entity_removes_cell() {
+        let mut location_map = LocationMap::new();
+        location_map.add_entity(1, 0, 0);
+        location_map.add_entity(2, 1, 1);
+
+        location_map.remove_entity(1);
+        assert_eq!(location_map.cell_count(), 1);
+        location_map.remove_entity(2);
+        assert_eq!(location_map.cell_count(), 0);
+        assert!(location_map.is_empty());
+    }
+
+    #[test]
+    fn negative_coordinates_round_down_to_cell() {
+        assert_eq!(Point { x: -1, y: -1 }.cell(), (-1, -1));
+        assert_eq!(Point { x: -8, y: 7 }.cell(), (-1, 0));
+        assert_eq!(Point { x: -9, y: 8 }.cell(), (-2, 1));
+    }
+
+    #[test]
+    fn rect_bounds_are_inclusive() {
+        let mut location_map = LocationMap::new();
+        location_map.add_entity(1, -4, -4);
+        location_map.add_entity(2, 4, 4);
+        location_map.add_entity(3, 5, 4);
+        location_map.add_entity(4, -5, 0);
+
+        let entity_ids = ids_in_rect(&location_map, Point { x: -4, y: -4 }, Point { x: 4, y: 4 });
+        assert_eq!(entity_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn nearest_entity_prefers_closest_then_lowest_id() {
+        let mut location_map = LocationMap::new();
+        location_map.add_entity(3, 1, 0);
+        location_map.add_entity(2, 0, 1);
+        location_map.add_entity(1, 1, 1);
+
+        let origin = Point { x: 0, y: 0 };
+        assert_eq!(location_map.nearest_entity(&origin, 1), Some(2));
+        assert_eq!(location_map.nearest_entity(&Point { x: 5, y: 5 }, 1), None);
     }
 }
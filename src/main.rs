@@ -1,35 +1,82 @@
+mod assets;
+mod camera;
+mod console;
+mod cursor;
 mod entity;
+mod format;
+mod input;
 mod load;
 mod location;
 mod render;
+mod search;
 mod simulation;
-
-use entity::{EntityType, EntityTypeMap, OrbitalEntity};
-use location::{LocationMap, Point};
-use render::Viewport;
-use sdl2::event::Event;
+mod text_field;
+mod world;
+
+use anyhow::Context;
+use camera::Camera;
+use console::Console;
+use cursor::{CursorContext, Cursors};
+use entity::{EntityType, Orbit, StarClass};
+use input::{CameraBookmarks, DragPan, Measurement, ZoomAnchor, ZOOM_STEP};
+use location::Point;
+use render::{Minimap, Viewport};
+use sdl2::event::{Event, WindowEvent};
 use sdl2::image::{InitFlag, LoadTexture};
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::messagebox::{self, MessageBoxFlag};
+use sdl2::mouse::MouseButton;
+use search::Search;
+use simulation::GameLoop;
 use std::cmp::Ordering;
-use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
-use std::{path::Path, time::Duration};
-use tracing::{debug, info};
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::EnvFilter;
+use world::World;
 
 mod colors {
     use sdl2::pixels::Color;
 
     pub const BASE: Color = Color::RGB(36, 39, 58);
     pub const BLUE: Color = Color::RGB(138, 173, 244);
+    pub const OVERLAY: Color = Color::RGB(110, 115, 141);
     pub const WHITE: Color = Color::RGB(202, 211, 245);
 }
 
-const SIMULATION_UNIT_DURATION: Duration = Duration::from_millis(100);
-const SIMULATION_UNIT_BUDGET: Duration = SIMULATION_UNIT_DURATION;
+const WINDOW_WIDTH: u32 = 576;
+const WINDOW_HEIGHT: u32 = 576;
+
+/// How many frames per second we render when no `--fps` is given.
+const DEFAULT_RENDER_RATE: u32 = 60;
+
+/// Even when a loop blew through its budget we sleep at least this long, so a machine that can't
+/// keep up still gets time for other work instead of us pinning a core.
+const MIN_LOOP_SLEEP: Duration = Duration::from_millis(1);
+
+/// While the window is unfocused or minimized we wake up and render at most once per this
+/// duration, instead of once per frame.
+const UNFOCUSED_FRAME_DURATION: Duration = Duration::from_millis(500);
 
 type SimulationUnit = u32;
 
+/// Reads the value following `name` on the command line, e.g. `--seed 42`.
+fn parse_arg<T: FromStr>(name: &str) -> Option<T> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next().and_then(|value| value.parse().ok());
+        }
+    }
+    None
+}
+
+/// Whether `name` appears on the command line, e.g. `--pause-unfocused`.
+fn has_flag(name: &str) -> bool {
+    std::env::args().skip(1).any(|arg| arg == name)
+}
+
 pub fn main() {
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
@@ -37,57 +84,123 @@ pub fn main() {
 
     info!("starting sim");
 
+    if let Err(err) = run() {
+        // The alternate format includes the chain of causes, e.g. which SDL driver was missing.
+        let message = format!("{err:#}");
+        error!(%message, "sim failed");
+
+        // Showing a message box can itself fail when there is no display at all, in which case the
+        // log line above is all the user gets.
+        let _ = messagebox::show_simple_message_box(
+            MessageBoxFlag::ERROR,
+            "sim failed to start",
+            &message,
+            None,
+        );
+
+        std::process::exit(1);
+    }
+}
+
+fn run() -> anyhow::Result<()> {
     debug!("setting up SDL context");
-    let sdl_context = sdl2::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
-    let _image_context = sdl2::image::init(InitFlag::PNG).unwrap();
+    let sdl_context = sdl2::init()
+        .map_err(anyhow::Error::msg)
+        .context("failed to initialize SDL")?;
+    let video_subsystem = sdl_context
+        .video()
+        .map_err(anyhow::Error::msg)
+        .context("failed to initialize video, is a display available?")?;
+    let _image_context = sdl2::image::init(InitFlag::PNG)
+        .map_err(anyhow::Error::msg)
+        .context("failed to initialize SDL_image PNG support")?;
 
     debug!("creating SDL window");
     let window = video_subsystem
-        .window("sim", 576, 576)
+        .window("sim", WINDOW_WIDTH, WINDOW_HEIGHT)
         .position_centered()
         .build()
-        .unwrap();
+        .context("failed to create window")?;
 
     debug!("creating SDL canvas");
-    let mut canvas = window.into_canvas().software().build().unwrap();
+    let mut canvas = window
+        .into_canvas()
+        .software()
+        .build()
+        .context("failed to create canvas")?;
 
     debug!("loading tiles texture");
     let texture_creator = canvas.texture_creator();
-    let mut tiles_texture = texture_creator
-        .load_texture(Path::new("res/taffer.png"))
-        .unwrap();
+    let tiles_texture_on_disk = assets::find_asset(assets::TILESET_FILE_NAME).and_then(|path| {
+        debug!(path = %path.display(), "found tileset");
+        texture_creator
+            .load_texture(&path)
+            .inspect_err(|err| warn!(path = %path.display(), %err, "failed to load tileset"))
+            .ok()
+    });
+    let mut tiles_texture = match tiles_texture_on_disk {
+        Some(texture) => texture,
+        None => {
+            warn!("no usable tileset found on disk, using embedded tileset");
+            texture_creator
+                .load_texture_bytes(assets::FALLBACK_TILESET)
+                .map_err(anyhow::Error::msg)
+                .context("failed to load the embedded tileset")?
+        }
+    };
 
-    let mut entities = vec![];
-    let mut entity_type_map: EntityTypeMap = HashMap::new();
-    let mut location_map = LocationMap::new();
+    // A world seed from `--seed` lets a galaxy be recreated exactly.
+    let seed = parse_arg("--seed").unwrap_or_else(rand::random);
+    info!(seed, "creating world");
+    let mut world = World::new(seed);
     let mut location_viewport = Viewport::default();
-
-    // Add Sol
-    let sol_id = 0;
-    entities.push(sol_id);
-    entity_type_map.insert(sol_id, EntityType::Star);
-    location_map.add_entity(sol_id, 0, 0);
-
-    // Add Earth
-    let earth_id = 1;
-    entities.push(earth_id);
-    entity_type_map.insert(earth_id, EntityType::Planet);
-    location_map.add_entity(earth_id, -16, 0);
-
-    // Add Moon
-    let moon_id = 2;
-    entities.push(moon_id);
-    entity_type_map.insert(moon_id, EntityType::Moon);
-    location_map.add_entity(moon_id, -16, 2);
-
-    let mut event_pump = sdl_context.event_pump().unwrap();
+    let ease_duration = parse_arg("--camera-ease-ms")
+        .map(Duration::from_millis)
+        .unwrap_or(camera::DEFAULT_EASE_DURATION);
+    let mut camera = Camera::new(ease_duration);
+    let minimap = Minimap::new(WINDOW_WIDTH, WINDOW_HEIGHT, 48);
+
+    let sol_id = world.spawn(EntityType::Star, 0, 0);
+    let earth_id = world
+        .spawn_orbital(EntityType::Planet, sol_id, Orbit::circular(16.0), 0.0, 0.1)
+        .map_err(anyhow::Error::msg)?;
+    let moon_id = world
+        .spawn_orbital(EntityType::Moon, earth_id, Orbit::circular(2.0), 0.0, 0.2)
+        .map_err(anyhow::Error::msg)?;
+    world
+        .spawn_asteroid_belt(sol_id, 24.0, 48, 0.05)
+        .map_err(anyhow::Error::msg)?;
+    world.set_name(sol_id, "Sol");
+    world.star_classes.insert(sol_id, StarClass::G);
+    world.set_name(earth_id, "Earth");
+    world.set_name(moon_id, "Moon");
+
+    let mut event_pump = sdl_context
+        .event_pump()
+        .map_err(anyhow::Error::msg)
+        .context("failed to create SDL event pump")?;
+    let mut cursors = Cursors::new();
+    let mut drag_pan = DragPan::default();
+    let mut measure_key_held = false;
+    let mut measurement: Option<Measurement> = None;
+    let mut camera_bookmarks = CameraBookmarks::default();
+
+    // SDL starts with text input enabled, we only want it while the console or search is open.
+    let text_input = video_subsystem.text_input();
+    text_input.stop();
+    let mut console = Console::default();
+    let mut search = Search::default();
+
+    let clipboard = video_subsystem.clipboard();
+    let mut info_panel_open = false;
+    let mut debug_overlay_open = false;
+    let mut seed_copied = false;
 
     // Tracks how much time has passed since we started counting up to one second.
     let mut loop_start;
     let mut simulation_load_history = VecDeque::from(vec!['?', '?', '?', '?', '?']);
 
-    // Tracks how many simulation units (loops) were completed.
+    // Tracks how many simulation units were completed.
     let mut last_second_start = Instant::now();
     let mut simulation_units_counter: SimulationUnit = 0;
     let mut simulation_units_per_second: SimulationUnit = 0;
@@ -96,41 +209,270 @@ pub fn main() {
 
     let mut entity_focus_index = 0;
 
-    // Initialize orbital entities
-    let mut orbital_entities = vec![
-        OrbitalEntity {
-            id: earth_id,
-            anchor_id: sol_id,
-            radius: 16.0,
-            angle: 0.0,
-            angular_velocity: 0.1,
-            position: Point { x: 0, y: 0 },
-        },
-        OrbitalEntity {
-            id: moon_id,
-            anchor_id: earth_id,
-            radius: 2.0,
-            angle: 0.0,
-            angular_velocity: 0.2,
-            position: Point { x: 0, y: 0 },
-        },
-    ];
+    // Whether the simulation had to drop time during the last second, meaning it runs slower than
+    // real time.
+    let mut running_behind = false;
+    let mut dropped_time_this_second = false;
+
+    let mut window_focused = true;
+    let mut last_render = Instant::now();
+
+    let render_rate = parse_arg("--fps")
+        .filter(|&fps| fps > 0)
+        .unwrap_or(DEFAULT_RENDER_RATE);
+    let frame_duration = Duration::from_secs_f64(1.0 / render_rate as f64);
+    info!(render_rate, "rendering at fixed rate");
+
+    // Keyboard zoom keeps the point under the cursor in place by default, `--zoom-anchor center`
+    // zooms at the center of the window instead.
+    let zoom_anchor = parse_arg("--zoom-anchor").unwrap_or(ZoomAnchor::Cursor);
+    let mut mouse_in_window = true;
+
+    let mut game_loop = GameLoop::new();
+
+    // With `--pause-unfocused` the simulation stops while the window is unfocused or minimized.
+    let pause_unfocused = has_flag("--pause-unfocused");
+
+    // Time spent working, rather than sleeping, since the last load sample.
+    let mut busy_time = Duration::ZERO;
+    let mut load_sample_start = Instant::now();
 
     'running: loop {
         // Mark loop start.
         loop_start = Instant::now();
 
-        // Update positions of orbital entities
-        simulation::update_orbital_entities(&mut orbital_entities, &mut location_map);
+        let step = game_loop.step();
+        dropped_time_this_second |= step.dropped_time;
+
+        // We print a load indicator. This is a simple measure of how much of the time budget a
+        // single Simulation Unit has, namely 100ms, we spend working. We sample it by real time
+        // rather than per simulation unit, so it reads the same at any speed and while paused. 0
+        // indicates low load, 9 high.
+        let load_sample_elapsed = load_sample_start.elapsed();
+        if load_sample_elapsed >= simulation::SIMULATION_UNIT_DURATION {
+            let busy_time_per_unit = busy_time.mul_f64(
+                simulation::SIMULATION_UNIT_DURATION.as_secs_f64()
+                    / load_sample_elapsed.as_secs_f64(),
+            );
+            simulation_load_history.pop_front();
+            let load_indicator = load::get_load_indicator_from_duration(busy_time_per_unit);
+            simulation_load_history.push_back(load_indicator);
+            busy_time = Duration::ZERO;
+            load_sample_start = Instant::now();
+        }
+
+        for _ in 0..step.simulation_units {
+            world.locations.remember_previous_locations();
+
+            // Update positions of orbital entities
+            simulation::update_orbital_entities(&mut world.orbital_entities, &mut world.locations);
+            world.elapsed_simulation_units += 1;
+
+            simulation_units_counter += 1;
+        }
+
+        let mouse_state = event_pump.mouse_state();
+        let (keyboard_zoom_x, keyboard_zoom_y) = zoom_anchor.point(
+            mouse_in_window.then(|| (mouse_state.x(), mouse_state.y())),
+            WINDOW_WIDTH,
+            WINDOW_HEIGHT,
+        );
 
         // Handle events.
         for event in event_pump.poll_iter() {
+            if let Event::Window { win_event, .. } = event {
+                match win_event {
+                    WindowEvent::FocusLost | WindowEvent::Minimized => window_focused = false,
+                    WindowEvent::FocusGained | WindowEvent::Restored => window_focused = true,
+                    WindowEvent::Enter => mouse_in_window = true,
+                    WindowEvent::Leave => mouse_in_window = false,
+                    _ => {}
+                }
+                game_loop.suspended = pause_unfocused && !window_focused;
+            }
+
+            // Releases are handled even while the console or search captures input. Otherwise a key
+            // or button let go over them would still count as held once they close.
+            match event {
+                Event::KeyUp {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => {
+                    measure_key_held = false;
+                }
+                Event::MouseButtonUp { mouse_btn, .. } => {
+                    if mouse_btn == MouseButton::Left {
+                        measurement = None;
+                    }
+                    drag_pan.end(mouse_btn);
+                }
+                _ => {}
+            }
+
+            // While the console is open it captures the keyboard.
+            if console.open {
+                match event {
+                    Event::Quit { .. } => break 'running,
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Escape | Keycode::Backquote),
+                        ..
+                    } => {
+                        console.toggle();
+                        text_input.stop();
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Backspace),
+                        ..
+                    } => console.input.backspace(),
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Return | Keycode::KpEnter),
+                        ..
+                    } => console.submit(&mut world, &mut location_viewport),
+                    Event::KeyDown {
+                        keycode: Some(Keycode::V),
+                        keymod,
+                        ..
+                    } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                        console.input.paste(&clipboard)
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::C),
+                        keymod,
+                        ..
+                    } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                        console.input.copy(&clipboard)
+                    }
+                    Event::TextInput { text, .. } => console.input.push_str(&text),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Like the console, the search captures the keyboard while open.
+            if search.open {
+                match event {
+                    Event::Quit { .. } => break 'running,
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Escape),
+                        ..
+                    } => {
+                        search.close();
+                        text_input.stop();
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Backspace),
+                        ..
+                    } => {
+                        search.query.backspace();
+                        search.refresh(&world);
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::V),
+                        keymod,
+                        ..
+                    } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                        search.query.paste(&clipboard);
+                        search.refresh(&world);
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::C),
+                        keymod,
+                        ..
+                    } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                        search.query.copy(&clipboard)
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Up),
+                        ..
+                    } => search.select_previous(),
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Down | Keycode::Tab),
+                        ..
+                    } => search.select_next(),
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Return | Keycode::KpEnter),
+                        ..
+                    } => {
+                        // Focusing the match the way tab does means tab continues from it.
+                        if let Some(entity_id) = search.selected_entity() {
+                            if let Some(index) =
+                                world.entities.iter().position(|&id| id == entity_id)
+                            {
+                                entity_focus_index = index;
+                            }
+                            let Point { x: ex, y: ey } =
+                                world.locations.get(&entity_id).cloned().unwrap_or_default();
+                            camera.center_on(&mut location_viewport, ex, ey);
+                        }
+                        search.close();
+                        text_input.stop();
+                    }
+                    Event::TextInput { text, .. } => {
+                        search.query.push_str(&text);
+                        search.refresh(&world);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Bookmark keys are handled ahead of the match, a match guard can't bind the slot.
+            if let Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } = event
+            {
+                if let Some(slot) = CameraBookmarks::slot(keycode) {
+                    if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                        camera_bookmarks.save(slot, &location_viewport);
+                    } else if let Some(bookmark) = camera_bookmarks.get(slot) {
+                        camera.move_to(&mut location_viewport, bookmark.anchor, bookmark.zoom);
+                    }
+                    continue;
+                }
+            }
+
             match event {
                 Event::Quit { .. }
                 | Event::KeyDown {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backquote),
+                    ..
+                } => {
+                    console.toggle();
+                    text_input.start();
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Slash | Keycode::KpDivide),
+                    ..
+                } => {
+                    search.open();
+                    search.refresh(&world);
+                    text_input.start();
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::I),
+                    ..
+                } => {
+                    info_panel_open = !info_panel_open;
+                    seed_copied = false;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::F3),
+                    ..
+                } => debug_overlay_open = !debug_overlay_open,
+                Event::KeyDown {
+                    keycode: Some(Keycode::C),
+                    ..
+                } if info_panel_open => match clipboard.set_clipboard_text(&world.seed.to_string())
+                {
+                    Ok(()) => seed_copied = true,
+                    Err(err) => warn!(%err, "failed to copy seed to clipboard"),
+                },
                 Event::KeyDown {
                     keycode: Some(Keycode::Up),
                     ..
@@ -159,50 +501,244 @@ pub fn main() {
                     keycode: Some(Keycode::Tab),
                     ..
                 } => {
-                    entity_focus_index = (entity_focus_index + 1) % entities.len();
-                    let entity_id = entities[entity_focus_index];
+                    if world.entities.is_empty() {
+                        continue;
+                    }
+                    entity_focus_index = (entity_focus_index + 1) % world.entities.len();
+                    let entity_id = world.entities[entity_focus_index];
                     let Point { x: ex, y: ey } =
-                        location_map.get(&entity_id).cloned().unwrap_or_default();
-                    location_viewport.center_on_entity(ex, ey);
+                        world.locations.get(&entity_id).cloned().unwrap_or_default();
+                    camera.center_on(&mut location_viewport, ex, ey);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    repeat: false,
+                    ..
+                } => game_loop.toggle_pause(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::LeftBracket),
+                    ..
+                } => game_loop.slow_down(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::RightBracket),
+                    ..
+                } => game_loop.speed_up(),
+                // Holding the key steps continuously through key repeat.
+                Event::KeyDown {
+                    keycode: Some(Keycode::Period),
+                    ..
+                } => game_loop.advance_one(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::Equals | Keycode::Plus | Keycode::KpPlus),
+                    ..
+                } => {
+                    location_viewport.zoom_at(
+                        location_viewport.zoom + ZOOM_STEP,
+                        keyboard_zoom_x,
+                        keyboard_zoom_y,
+                    );
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Minus | Keycode::KpMinus),
+                    ..
+                } => {
+                    location_viewport.zoom_at(
+                        location_viewport.zoom - ZOOM_STEP,
+                        keyboard_zoom_x,
+                        keyboard_zoom_y,
+                    );
+                }
+                Event::MouseWheel {
+                    y,
+                    mouse_x,
+                    mouse_y,
+                    ..
+                } => {
+                    location_viewport.zoom_at(
+                        location_viewport.zoom + y.signum() as f64 * ZOOM_STEP,
+                        mouse_x,
+                        mouse_y,
+                    );
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => {
+                    measure_key_held = true;
+                }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if minimap.contains(x, y) => {
+                    let Point { x: wx, y: wy } = minimap.minimap_to_world(x, y);
+                    camera.center_on(&mut location_viewport, wx, wy);
+                }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if measure_key_held => {
+                    let point = location_viewport.screen_to_world(x, y);
+                    measurement = Some(Measurement {
+                        start: point,
+                        end: point,
+                    });
+                }
+                Event::MouseButtonDown {
+                    mouse_btn, x, y, ..
+                } => {
+                    drag_pan.begin(mouse_btn, x, y, location_viewport.anchor);
+                }
+                Event::MouseMotion { x, y, .. } => {
+                    if let Some(measurement) = measurement.as_mut() {
+                        measurement.end = location_viewport.screen_to_world(x, y);
+                    }
+                    if let Some(anchor) =
+                        drag_pan.update(x, y, location_viewport.tile_pixel_width())
+                    {
+                        location_viewport.anchor = anchor;
+                    }
                 }
                 _ => {}
             }
         }
 
-        canvas.clear();
+        camera.update(&mut location_viewport);
+
+        // Interface elements are drawn on top of the universe, entities hidden underneath them
+        // can't be hovered.
+        let (mouse_x, mouse_y) = (mouse_state.x(), mouse_state.y());
+        let over_interface = minimap.contains(mouse_x, mouse_y)
+            || render::bookmark_indicators_contain(mouse_x, mouse_y)
+            || (console.open && render::console_contains(mouse_x, mouse_y))
+            || (search.open && render::search_contains(mouse_x, mouse_y))
+            || (info_panel_open && render::info_panel_contains(mouse_x, mouse_y))
+            || (debug_overlay_open && render::debug_overlay_contains(mouse_x, mouse_y));
+
+        // Entities move under a resting mouse too, so we pick every loop rather than on motion. We
+        // accept entities on the tiles around the cursor as well, small moving targets are hard
+        // to hit exactly.
+        let hovered_entity = if over_interface {
+            None
+        } else {
+            let mouse_point = location_viewport.screen_to_world(mouse_x, mouse_y);
+            world.locations.nearest_entity(&mouse_point, 1)
+        };
+        cursors.set_context(match hovered_entity {
+            Some(_) => CursorContext::Entity,
+            None => CursorContext::EmptySpace,
+        });
+
+        let tooltip = if minimap.contains(mouse_x, mouse_y) {
+            Some(vec!["CLICK TO JUMP HERE".to_string()])
+        } else if render::bookmark_indicators_contain(mouse_x, mouse_y) {
+            Some(vec![
+                "CTRL+F5-F8 SAVES VIEW".to_string(),
+                "F5-F8 JUMPS BACK".to_string(),
+            ])
+        } else {
+            hovered_entity.and_then(|entity_id| {
+                let name = world.names.get(&entity_id)?;
+                let entity_type = world.entity_types.get(&entity_id)?;
+                let description = match world.star_classes.get(&entity_id) {
+                    Some(star_class) => format!("Class {star_class} {entity_type}"),
+                    None => entity_type.to_string(),
+                };
+                Some(vec![name.clone(), description])
+            })
+        };
+
+        // While the window is in the background nobody is watching, so we draw far less often.
+        let render_this_loop = window_focused || last_render.elapsed() >= UNFOCUSED_FRAME_DURATION;
+        if render_this_loop {
+            last_render = Instant::now();
+
+            canvas.clear();
+
+            // Render our tiles.
+            render::render_viewport(
+                &mut canvas,
+                &mut tiles_texture,
+                &world.entity_types,
+                &world.star_classes,
+                &world.locations,
+                &location_viewport,
+                step.alpha,
+            );
+
+            if let Some((x, y)) = hovered_entity
+                .and_then(|entity_id| world.locations.interpolated_location(entity_id, step.alpha))
+            {
+                render::render_hover_highlight(&mut canvas, &location_viewport, x, y);
+            }
 
-        // Render our tiles.
-        render::render_viewport(
-            &mut canvas,
-            &mut tiles_texture,
-            &entity_type_map,
-            &location_map,
-            &location_viewport,
-        );
+            minimap.render(
+                &mut canvas,
+                &world.entity_types,
+                &world.locations,
+                &location_viewport,
+            );
+
+            if let Some(measurement) = &measurement {
+                render::render_measurement(
+                    &mut canvas,
+                    &mut tiles_texture,
+                    &location_viewport,
+                    measurement,
+                );
+            }
 
-        // Calculate how long we took to complete the loop, and report the simulation speed.
+            if info_panel_open {
+                render::render_info_panel(&mut canvas, &mut tiles_texture, &world, seed_copied);
+            }
 
-        // First we print a load indicator. This is a simple measure of how much time was left out
-        // of the time budget a single Simulation Unit has, namely 100ms. 0 indicates low load, 9
-        // high.
-        simulation_load_history.pop_front();
-        let loop_elapsed = loop_start.elapsed();
-        let load_indicator = load::get_load_indicator_from_duration(loop_elapsed);
-        simulation_load_history.push_back(load_indicator);
-        let simulation_load_history_text: String = simulation_load_history.iter().collect();
-
-        simulation_units_counter += 1;
-
-        render::render_status_text(
-            &mut canvas,
-            &mut tiles_texture,
-            &format!(
-                "LOAD {} SUPS {}",
-                simulation_load_history_text, simulation_units_per_second
-            ),
-            colors::BASE,
-            colors::WHITE,
-        );
+            if debug_overlay_open {
+                render::render_debug_overlay(&mut canvas, &mut tiles_texture, &world);
+            }
+
+            if search.open {
+                render::render_search(&mut canvas, &mut tiles_texture, &search, &world);
+            }
+
+            if console.open {
+                render::render_console(&mut canvas, &mut tiles_texture, &console);
+            }
+
+            render::render_bookmark_indicators(&mut canvas, &mut tiles_texture, &camera_bookmarks);
+
+            if let Some(lines) = &tooltip {
+                render::render_tooltip(
+                    &mut canvas,
+                    &mut tiles_texture,
+                    lines,
+                    mouse_state.x(),
+                    mouse_state.y(),
+                );
+            }
+
+            render::render_status_text(
+                &mut canvas,
+                &mut tiles_texture,
+                &format!(
+                    "{}{}LOAD {} SUPS {}",
+                    if game_loop.is_paused() {
+                        "PAUSED ".to_string()
+                    } else {
+                        format!("{}X ", game_loop.speed())
+                    },
+                    if running_behind { "BEHIND " } else { "" },
+                    simulation_load_history.iter().collect::<String>(),
+                    format::format_quantity(simulation_units_per_second as f64)
+                ),
+                colors::BASE,
+                colors::WHITE,
+            );
+
+            canvas.present();
+        }
 
         // We update an indication of how many Simulation Units we're completing per second. Ideally this is
         // 10.
@@ -211,16 +747,28 @@ pub fn main() {
             Ordering::Equal | Ordering::Greater => {
                 simulation_units_per_second = simulation_units_counter;
                 simulation_units_counter = 0;
+                running_behind = dropped_time_this_second;
+                dropped_time_this_second = false;
                 last_second_start = Instant::now();
             }
         }
 
-        canvas.present();
-
-        // Sleep the rest of our budget.
-        let simulation_unit_budget_left =
-            SIMULATION_UNIT_BUDGET.as_millis() as i64 - loop_elapsed.as_millis() as i64;
-        let duration_to_sleep = Duration::from_millis(simulation_unit_budget_left.max(0) as u64);
+        let loop_elapsed = loop_start.elapsed();
+        busy_time += loop_elapsed;
+
+        // Sleep the rest of the frame. In the background we sleep far longer, waking up at 60Hz
+        // just to poll for events would keep draining the battery.
+        let loop_duration = if window_focused {
+            frame_duration
+        } else {
+            UNFOCUSED_FRAME_DURATION
+        };
+        let duration_to_sleep = loop_duration
+            .checked_sub(loop_elapsed)
+            .unwrap_or_default()
+            .max(MIN_LOOP_SLEEP);
         std::thread::sleep(duration_to_sleep);
     }
+
+    Ok(())
 }
@@ -0,0 +1,37 @@
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::Window;
+
+use crate::colors;
+use crate::console::{Console, MAX_OUTPUT_LINES};
+
+use super::{render_text, tileset};
+
+/// Whether the given pixel position lies on the console, output and input line alike.
+pub fn console_contains(x: i32, y: i32) -> bool {
+    tileset::make_multi_tile_rect(0, 1, 64, MAX_OUTPUT_LINES as u8 + 1).contains_point((x, y))
+}
+
+/// Draws the console across the full width of the window, just below the status line.
+pub fn render_console(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    console: &Console,
+) {
+    let padding = MAX_OUTPUT_LINES - console.output.len();
+    let lines = std::iter::repeat_n(String::new(), padding)
+        .chain(console.output.iter().cloned())
+        .chain(std::iter::once(format!("> {}_", console.input.as_str())));
+
+    for (i, line) in lines.enumerate() {
+        let line: String = format!("{line:<64}").chars().take(64).collect();
+        render_text(
+            canvas,
+            tiles_texture,
+            &line,
+            0,
+            1 + i as u8,
+            colors::BASE,
+            colors::WHITE,
+        );
+    }
+}
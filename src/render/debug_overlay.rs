@@ -0,0 +1,59 @@
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::Window;
+
+use crate::colors;
+use crate::format::format_quantity;
+use crate::world::World;
+
+use super::{render_panel, tileset};
+
+const OVERLAY_WIDTH: usize = 20;
+const OVERLAY_HEIGHT: usize = 7;
+
+/// Whether the given pixel position lies on the debug overlay.
+pub fn debug_overlay_contains(x: i32, y: i32) -> bool {
+    tileset::make_multi_tile_rect(
+        (64 - OVERLAY_WIDTH) as u8,
+        1,
+        OVERLAY_WIDTH as u8,
+        OVERLAY_HEIGHT as u8,
+    )
+    .contains_point((x, y))
+}
+
+/// Shows how large the world's component collections have grown, in the top right corner below the
+/// status line. Useful for spotting unbounded growth in long sessions.
+pub fn render_debug_overlay(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    world: &World,
+) {
+    let lines: [String; OVERLAY_HEIGHT] = [
+        "COMPONENTS".to_string(),
+        format!("ENTITIES {}", format_quantity(world.entities.len() as f64)),
+        format!("TYPES {}", format_quantity(world.entity_types.len() as f64)),
+        format!("NAMES {}", format_quantity(world.names.len() as f64)),
+        format!(
+            "LOCATIONS {}",
+            format_quantity(world.locations.len() as f64)
+        ),
+        format!(
+            "GRID CELLS {}",
+            format_quantity(world.locations.cell_count() as f64)
+        ),
+        format!(
+            "ORBITALS {}",
+            format_quantity(world.orbital_entities.len() as f64)
+        ),
+    ];
+
+    render_panel(
+        canvas,
+        tiles_texture,
+        &lines,
+        (64 - OVERLAY_WIDTH) as u8,
+        1,
+        OVERLAY_WIDTH,
+        colors::BASE,
+    );
+}
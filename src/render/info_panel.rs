@@ -0,0 +1,74 @@
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::Window;
+
+use crate::colors;
+use crate::entity::EntityType;
+use crate::format::format_quantity;
+use crate::simulation::SIMULATION_UNIT_DURATION;
+use crate::world::World;
+
+use super::{render_panel, tileset};
+
+const PANEL_WIDTH: usize = 24;
+const PANEL_HEIGHT: usize = 9;
+
+/// Whether the given pixel position lies on the info panel.
+pub fn info_panel_contains(x: i32, y: i32) -> bool {
+    tileset::make_multi_tile_rect(0, 1, PANEL_WIDTH as u8, PANEL_HEIGHT as u8)
+        .contains_point((x, y))
+}
+
+/// Shows where the current galaxy came from and what is in it, in the top left corner.
+pub fn render_info_panel(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    world: &World,
+    seed_copied: bool,
+) {
+    let (mut stars, mut planets, mut moons, mut asteroids) = (0, 0, 0, 0);
+    for entity_type in world.entity_types.values() {
+        match entity_type {
+            EntityType::Asteroid => asteroids += 1,
+            EntityType::Moon => moons += 1,
+            EntityType::Planet => planets += 1,
+            EntityType::Space => {}
+            EntityType::Star => stars += 1,
+        }
+    }
+
+    // Simulated rather than wall clock time, it runs faster at higher speeds and stops while paused.
+    let sim_time_seconds =
+        (world.elapsed_simulation_units as f64 * SIMULATION_UNIT_DURATION.as_secs_f64()) as u64;
+
+    let lines: [String; PANEL_HEIGHT] = [
+        "GALAXY".to_string(),
+        // A seed can be up to 20 digits, too long to share a line with its label.
+        "SEED".to_string(),
+        world.seed.to_string(),
+        format!("STARS {}", format_quantity(stars as f64)),
+        format!("PLANETS {}", format_quantity(planets as f64)),
+        format!("MOONS {}", format_quantity(moons as f64)),
+        format!("ASTEROIDS {}", format_quantity(asteroids as f64)),
+        format!(
+            "SIM TIME {:02}:{:02}:{:02}",
+            sim_time_seconds / 3600,
+            sim_time_seconds / 60 % 60,
+            sim_time_seconds % 60
+        ),
+        if seed_copied {
+            "SEED COPIED".to_string()
+        } else {
+            "C TO COPY SEED".to_string()
+        },
+    ];
+
+    render_panel(
+        canvas,
+        tiles_texture,
+        &lines,
+        0,
+        1,
+        PANEL_WIDTH,
+        colors::BASE,
+    );
+}
@@ -0,0 +1,99 @@
+use sdl2::rect::{Point as SdlPoint, Rect};
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+
+use crate::colors;
+use crate::entity::{EntityType, EntityTypeMap};
+use crate::location::{LocationMap, Point};
+
+use super::Viewport;
+
+const MINIMAP_PIXEL_SIZE: u32 = 128;
+const MINIMAP_MARGIN: i32 = 9;
+
+/// A fixed-scale overview of the universe in the bottom right corner of the window. It has its own
+/// projection, so it shows the same area no matter where the main viewport is looking.
+pub struct Minimap {
+    rect: Rect,
+    /// How many universe coordinates away from the origin the minimap reaches in each direction.
+    world_radius: i32,
+}
+
+impl Minimap {
+    pub fn new(window_width: u32, window_height: u32, world_radius: i32) -> Self {
+        Self {
+            rect: Rect::new(
+                window_width as i32 - MINIMAP_PIXEL_SIZE as i32 - MINIMAP_MARGIN,
+                window_height as i32 - MINIMAP_PIXEL_SIZE as i32 - MINIMAP_MARGIN,
+                MINIMAP_PIXEL_SIZE,
+                MINIMAP_PIXEL_SIZE,
+            ),
+            world_radius,
+        }
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.rect.contains_point((x, y))
+    }
+
+    fn world_to_minimap(&self, point: &Point) -> SdlPoint {
+        let scale = MINIMAP_PIXEL_SIZE as f64 / (self.world_radius * 2) as f64;
+        SdlPoint::new(
+            self.rect.x() + ((point.x + self.world_radius) as f64 * scale) as i32,
+            self.rect.y() + ((point.y + self.world_radius) as f64 * scale) as i32,
+        )
+    }
+
+    /// Translates a pixel position inside the minimap to the universe coordinate it represents.
+    pub fn minimap_to_world(&self, x: i32, y: i32) -> Point {
+        let scale = (self.world_radius * 2) as f64 / MINIMAP_PIXEL_SIZE as f64;
+        Point {
+            x: ((x - self.rect.x()) as f64 * scale) as i32 - self.world_radius,
+            y: ((y - self.rect.y()) as f64 * scale) as i32 - self.world_radius,
+        }
+    }
+
+    pub fn render(
+        &self,
+        canvas: &mut Canvas<Window>,
+        entity_type_map: &EntityTypeMap,
+        location_map: &LocationMap,
+        viewport: &Viewport,
+    ) {
+        canvas.set_draw_color(colors::BASE);
+        canvas.fill_rect(self.rect).unwrap();
+
+        // Anything outside the minimap's reach, including most of the viewport rectangle when
+        // zoomed far away from the origin, is clipped rather than drawn over the map.
+        canvas.set_clip_rect(self.rect);
+
+        for (entity_id, point) in location_map.iter() {
+            let color = match entity_type_map.get(entity_id) {
+                Some(EntityType::Star) => colors::WHITE,
+                _ => colors::BLUE,
+            };
+            canvas.set_draw_color(color);
+            canvas.draw_point(self.world_to_minimap(point)).unwrap();
+        }
+
+        let top_left = self.world_to_minimap(&viewport.anchor);
+        let bottom_right = self.world_to_minimap(&Point {
+            x: viewport.max_x(),
+            y: viewport.max_y(),
+        });
+        canvas.set_draw_color(colors::OVERLAY);
+        canvas
+            .draw_rect(Rect::new(
+                top_left.x(),
+                top_left.y(),
+                (bottom_right.x() - top_left.x()).max(1) as u32,
+                (bottom_right.y() - top_left.y()).max(1) as u32,
+            ))
+            .unwrap();
+
+        canvas.set_clip_rect(None);
+
+        canvas.set_draw_color(colors::OVERLAY);
+        canvas.draw_rect(self.rect).unwrap();
+    }
+}
@@ -1,4 +1,18 @@
+mod console;
+mod debug_overlay;
+mod info_panel;
+mod minimap;
+mod search;
 mod tileset;
+mod tooltip;
+
+pub use console::{console_contains, render_console};
+pub use debug_overlay::{debug_overlay_contains, render_debug_overlay};
+pub use info_panel::{info_panel_contains, render_info_panel};
+pub use minimap::Minimap;
+pub use search::{render_search, search_contains};
+pub use tileset::supports_char;
+pub use tooltip::render_tooltip;
 
 use std::collections::HashMap;
 
@@ -7,17 +21,24 @@ use sdl2::rect::Rect;
 use sdl2::render::{Canvas, Texture};
 use sdl2::video::Window;
 
-use crate::entity::EntityId;
+use crate::entity::{EntityId, StarClass};
+use crate::format::format_quantity;
+use crate::input::{CameraBookmarks, Measurement, BOOKMARK_KEYS};
 use crate::location::{LocationMap, Point};
 use crate::{colors, EntityType};
 
 pub const TILE_PIXEL_WIDTH: u8 = 9;
 
+pub const MIN_ZOOM: f64 = 1.0;
+pub const MAX_ZOOM: f64 = 4.0;
+
 pub struct Renderable {
     pub color: Color,
     pub tileset_rect: Rect,
-    pub x: u8,
-    pub y: u8,
+    /// Pixel position of the top left corner. Entities are placed by pixel rather than by tile so
+    /// they can move smoothly between tiles.
+    pub x: i32,
+    pub y: i32,
 }
 
 pub fn render_status_text(
@@ -26,15 +47,32 @@ pub fn render_status_text(
     text: &str,
     background_color: Color,
     foreground_color: Color,
+) {
+    render_text(
+        canvas,
+        tiles_texture,
+        text,
+        (64 - text.len()) as u8,
+        0,
+        background_color,
+        foreground_color,
+    );
+}
+
+/// Renders a single line of text starting at the given tile position, at native tile size
+/// regardless of the viewport zoom.
+pub fn render_text(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    text: &str,
+    x: u8,
+    y: u8,
+    background_color: Color,
+    foreground_color: Color,
 ) {
     canvas.set_draw_color(background_color);
     canvas
-        .draw_rect(tileset::make_multi_tile_rect(
-            (64 - text.len()) as u8,
-            0,
-            text.len() as u8,
-            1,
-        ))
+        .fill_rect(tileset::make_multi_tile_rect(x, y, text.len() as u8, 1))
         .unwrap();
 
     tiles_texture.set_color_mod(foreground_color.r, foreground_color.g, foreground_color.b);
@@ -46,19 +84,45 @@ pub fn render_status_text(
             .copy(
                 tiles_texture,
                 Some(tileset::rect_from_char(char)),
-                Some(tileset::make_tile_rect(
-                    (64 - text.len() + i).try_into().unwrap(),
-                    0,
-                )),
+                Some(tileset::make_tile_rect(x + i as u8, y)),
             )
             .unwrap();
     }
 }
 
+/// Renders lines of text as a block `width` tiles wide with its top left corner at the given tile
+/// position. Each line is indented by one tile and padded or cut off to the full width.
+pub fn render_panel(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    lines: &[String],
+    x: u8,
+    y: u8,
+    width: usize,
+    background_color: Color,
+) {
+    for (i, line) in lines.iter().enumerate() {
+        let line: String = format!(" {line:<width$}", width = width - 1)
+            .chars()
+            .take(width)
+            .collect();
+        render_text(
+            canvas,
+            tiles_texture,
+            &line,
+            x,
+            y + i as u8,
+            background_color,
+            colors::WHITE,
+        );
+    }
+}
+
 fn render_tile(
     canvas: &mut Canvas<Window>,
     tiles_texture: &mut Texture<'_>,
     renderable: &Renderable,
+    tile_pixel_width: u32,
 ) {
     tiles_texture.set_color_mod(renderable.color.r, renderable.color.g, renderable.color.b);
 
@@ -67,44 +131,158 @@ fn render_tile(
             tiles_texture,
             Some(renderable.tileset_rect),
             Some(Rect::new(
-                renderable.x as i32 * TILE_PIXEL_WIDTH as i32,
-                renderable.y as i32 * TILE_PIXEL_WIDTH as i32,
-                TILE_PIXEL_WIDTH as u32,
-                TILE_PIXEL_WIDTH as u32,
+                renderable.x,
+                renderable.y,
+                tile_pixel_width,
+                tile_pixel_width,
             )),
         )
         .unwrap();
 }
 
+/// Roughly the color a star of the given class appears as, from blue-white to orange.
+fn star_class_color(star_class: StarClass) -> Color {
+    match star_class {
+        StarClass::O => Color::RGB(155, 176, 255),
+        StarClass::B => Color::RGB(170, 191, 255),
+        StarClass::A => Color::RGB(202, 215, 255),
+        StarClass::F => Color::RGB(248, 247, 255),
+        StarClass::G => Color::RGB(255, 244, 234),
+        StarClass::K => Color::RGB(255, 210, 161),
+        StarClass::M => Color::RGB(255, 204, 111),
+    }
+}
+
 pub fn render_viewport(
     canvas: &mut Canvas<Window>,
     tiles_texture: &mut Texture<'_>,
     entity_type_map: &HashMap<EntityId, EntityType>,
+    star_classes: &HashMap<EntityId, StarClass>,
     location_map: &LocationMap,
     viewport: &Viewport,
+    alpha: f64,
 ) {
-    let visible_entities = location_map.iter().filter(|(_, location)| {
-        location.x >= viewport.min_x()
-            && location.x <= viewport.max_x()
-            && location.y >= viewport.min_y()
-            && location.y <= viewport.max_y()
-    });
+    let visible_entities = location_map.entities_in_rect(
+        &Point {
+            x: viewport.min_x(),
+            y: viewport.min_y(),
+        },
+        &Point {
+            x: viewport.max_x(),
+            y: viewport.max_y(),
+        },
+    );
 
-    for (entity_id, point) in visible_entities {
-        let translated_location = LocationMap::translate_location(point, viewport);
+    for (entity_id, _) in visible_entities {
+        let Some((x, y)) = location_map.interpolated_location(entity_id, alpha) else {
+            continue;
+        };
+        let pixel = viewport.world_to_pixel(x, y);
 
         let entity_type = entity_type_map
-            .get(entity_id)
+            .get(&entity_id)
             .expect("expect entity type to be stored for entity id");
 
+        let (tileset_rect, color) = match star_classes.get(&entity_id) {
+            Some(&star_class) => (tileset::star_rect(star_class), star_class_color(star_class)),
+            None => (entity_type.into(), colors::BLUE),
+        };
+
         let renderable = Renderable {
-            x: translated_location.x as u8,
-            y: translated_location.y as u8,
-            tileset_rect: entity_type.into(),
-            color: colors::BLUE,
+            x: pixel.x,
+            y: pixel.y,
+            tileset_rect,
+            color,
         };
 
-        render_tile(canvas, tiles_texture, &renderable);
+        render_tile(
+            canvas,
+            tiles_texture,
+            &renderable,
+            viewport.tile_pixel_width(),
+        );
+    }
+}
+
+/// Outlines the entity under the mouse, so it is clear what a click would hit. Takes the same
+/// interpolated location the entity is drawn at.
+pub fn render_hover_highlight(canvas: &mut Canvas<Window>, viewport: &Viewport, x: f64, y: f64) {
+    let pixel = viewport.world_to_pixel(x, y);
+    let tile_pixel_width = viewport.tile_pixel_width();
+
+    canvas.set_draw_color(colors::OVERLAY);
+    canvas
+        .draw_rect(Rect::new(
+            pixel.x - 1,
+            pixel.y - 1,
+            tile_pixel_width + 2,
+            tile_pixel_width + 2,
+        ))
+        .unwrap();
+}
+
+/// Draws a line between the two measured universe coordinates, labelled with the distance.
+pub fn render_measurement(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    viewport: &Viewport,
+    measurement: &Measurement,
+) {
+    let start = viewport.world_to_screen(&measurement.start);
+    let end = viewport.world_to_screen(&measurement.end);
+
+    canvas.set_draw_color(colors::WHITE);
+    canvas
+        .draw_line((start.x, start.y), (end.x, end.y))
+        .unwrap();
+
+    let label = format_quantity(measurement.distance());
+    let label_x = (end.x / TILE_PIXEL_WIDTH as i32 + 1).clamp(0, 64 - label.len() as i32);
+    let label_y = (end.y / TILE_PIXEL_WIDTH as i32).clamp(0, 63);
+
+    render_text(
+        canvas,
+        tiles_texture,
+        &label,
+        label_x as u8,
+        label_y as u8,
+        colors::BASE,
+        colors::WHITE,
+    );
+}
+
+/// Each bookmark indicator takes up this many tiles, the key name followed by a space.
+const BOOKMARK_INDICATOR_WIDTH: i32 = 3;
+
+/// Whether the given pixel position lies on the bookmark indicators.
+pub fn bookmark_indicators_contain(x: i32, y: i32) -> bool {
+    let tile_pixel_width = TILE_PIXEL_WIDTH as i32;
+    (0..tile_pixel_width).contains(&y)
+        && (0..BOOKMARK_KEYS.len() as i32 * BOOKMARK_INDICATOR_WIDTH * tile_pixel_width)
+            .contains(&x)
+}
+
+/// Lists the bookmark keys in the top left corner, highlighting those with a bookmark saved.
+pub fn render_bookmark_indicators(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    bookmarks: &CameraBookmarks,
+) {
+    for (slot, key) in BOOKMARK_KEYS.iter().enumerate() {
+        let color = if bookmarks.is_set(slot) {
+            colors::WHITE
+        } else {
+            colors::OVERLAY
+        };
+        render_text(
+            canvas,
+            tiles_texture,
+            &key.name(),
+            (slot as i32 * BOOKMARK_INDICATOR_WIDTH) as u8,
+            0,
+            colors::BASE,
+            color,
+        );
     }
 }
 
@@ -112,8 +290,11 @@ pub struct Viewport {
     /// Specifies which universe coordinate the top left corner of the viewport is centered on.
     pub anchor: Point,
     /// Specifies how far we're zoomed in on the universe, and therefore how many tiles are visible.
+    /// Tiles are drawn `zoom` times their native size.
     pub zoom: f64,
+    /// Width of the viewport in tiles at a zoom of 1.
     pub width: u32,
+    /// Height of the viewport in tiles at a zoom of 1.
     pub height: u32,
 }
 
@@ -134,7 +315,7 @@ impl Viewport {
     }
 
     pub fn max_x(&self) -> i32 {
-        self.anchor.x + self.width as i32
+        self.anchor.x + self.visible_width()
     }
 
     pub fn min_y(&self) -> i32 {
@@ -142,11 +323,82 @@ impl Viewport {
     }
 
     pub fn max_y(&self) -> i32 {
-        self.anchor.y + self.height as i32
+        self.anchor.y + self.visible_height()
+    }
+
+    pub fn tile_pixel_width(&self) -> u32 {
+        (TILE_PIXEL_WIDTH as f64 * self.zoom).round() as u32
+    }
+
+    /// How many tiles fit across the viewport at the current zoom.
+    pub fn visible_width(&self) -> i32 {
+        (self.width as f64 / self.zoom).ceil() as i32
+    }
+
+    /// How many tiles fit down the viewport at the current zoom.
+    pub fn visible_height(&self) -> i32 {
+        (self.height as f64 / self.zoom).ceil() as i32
+    }
+
+    /// Translates a pixel position in the window to the universe coordinate of the tile under it.
+    pub fn screen_to_world(&self, x: i32, y: i32) -> Point {
+        let tile_pixel_width = self.tile_pixel_width() as i32;
+        Point {
+            x: self.anchor.x + x / tile_pixel_width,
+            y: self.anchor.y + y / tile_pixel_width,
+        }
+    }
+
+    /// Translates a, possibly fractional, universe coordinate to the pixel position of the top left
+    /// corner of a tile drawn there.
+    pub fn world_to_pixel(&self, x: f64, y: f64) -> Point {
+        let tile_pixel_width = self.tile_pixel_width() as f64;
+        Point {
+            x: ((x - self.anchor.x as f64) * tile_pixel_width).round() as i32,
+            y: ((y - self.anchor.y as f64) * tile_pixel_width).round() as i32,
+        }
+    }
+
+    /// Translates a universe coordinate to the pixel position of the center of its tile.
+    pub fn world_to_screen(&self, point: &Point) -> Point {
+        let tile_pixel_width = self.tile_pixel_width() as i32;
+        let translated_location = LocationMap::translate_location(point, self);
+        Point {
+            x: translated_location.x * tile_pixel_width + tile_pixel_width / 2,
+            y: translated_location.y * tile_pixel_width + tile_pixel_width / 2,
+        }
     }
 
     pub fn center_on_entity(&mut self, x: i32, y: i32) {
-        self.anchor.x = x - (self.width as i32 / 2);
-        self.anchor.y = y - (self.height as i32 / 2);
+        self.anchor = self.centered_anchor(x, y);
+    }
+
+    /// The anchor that would put the given universe coordinate in the middle of the viewport.
+    pub fn centered_anchor(&self, x: i32, y: i32) -> Point {
+        Point {
+            x: x - (self.visible_width() / 2),
+            y: y - (self.visible_height() / 2),
+        }
+    }
+
+    /// Changes the zoom level while keeping the universe coordinate under the given pixel position
+    /// in place. Both keyboard and mouse wheel zoom go through here.
+    pub fn zoom_at(&mut self, zoom: f64, x: i32, y: i32) {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+
+        // Moving the anchor at the clamp boundary would make the view creep on every extra
+        // zoom step, even though the scale no longer changes.
+        if zoom == self.zoom {
+            return;
+        }
+
+        let old_tile_pixel_width = self.tile_pixel_width() as f64;
+        self.zoom = zoom;
+        let new_tile_pixel_width = self.tile_pixel_width() as f64;
+
+        self.anchor.x +=
+            (x as f64 / old_tile_pixel_width - x as f64 / new_tile_pixel_width).round() as i32;
+        self.anchor.y +=
+            (y as f64 / old_tile_pixel_width - y as f64 / new_tile_pixel_width).round() as i32;
     }
 }
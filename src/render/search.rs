@@ -0,0 +1,57 @@
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::Window;
+
+use crate::colors;
+use crate::search::{Search, MAX_RESULTS};
+use crate::world::World;
+
+use super::{render_text, tileset};
+
+/// Width of the search overlay in tiles.
+const SEARCH_WIDTH: usize = 32;
+
+/// Whether the given pixel position lies on the search overlay.
+pub fn search_contains(x: i32, y: i32) -> bool {
+    tileset::make_multi_tile_rect(0, 1, SEARCH_WIDTH as u8, MAX_RESULTS as u8 + 1)
+        .contains_point((x, y))
+}
+
+/// Draws the search query with its matches below it, in the top left just below the status line.
+pub fn render_search(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    search: &Search,
+    world: &World,
+) {
+    let results = search.results.iter().enumerate().map(|(i, entity_id)| {
+        let marker = if i == search.selected { '>' } else { ' ' };
+        let name = world.names.get(entity_id).map_or("", String::as_str);
+        format!("{marker} {name}")
+    });
+    let lines = std::iter::once(format!("/ {}_", search.query.as_str()))
+        .chain(results)
+        .chain(
+            search
+                .results
+                .is_empty()
+                .then(|| "  NO MATCHES".to_string()),
+        )
+        .chain(std::iter::repeat(String::new()))
+        .take(MAX_RESULTS + 1);
+
+    for (i, line) in lines.enumerate() {
+        let line: String = format!("{line:<SEARCH_WIDTH$}")
+            .chars()
+            .take(SEARCH_WIDTH)
+            .collect();
+        render_text(
+            canvas,
+            tiles_texture,
+            &line,
+            0,
+            1 + i as u8,
+            colors::BASE,
+            colors::WHITE,
+        );
+    }
+}
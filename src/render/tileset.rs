@@ -1,7 +1,7 @@
 use lazy_static::lazy_static;
 use sdl2::rect::Rect;
 
-use crate::entity::EntityType;
+use crate::entity::{EntityType, StarClass};
 
 use super::TILE_PIXEL_WIDTH;
 
@@ -11,12 +11,15 @@ lazy_static! {
     static ref LOWER_M_TILE: Rect = make_tile_rect(13, 6);
     static ref LOWER_P_TILE: Rect = make_tile_rect(0, 7);
     static ref LOWER_S_TILE: Rect = make_tile_rect(3, 7);
+    static ref SMALL_DOT_TILE: Rect = make_tile_rect(9, 15);
+    static ref UPPER_S_TILE: Rect = make_tile_rect(3, 5);
 }
 
 impl From<&EntityType> for Rect {
     fn from(entity: &EntityType) -> Self {
         use EntityType::*;
         match entity {
+            Asteroid => *SMALL_DOT_TILE,
             Moon => *LOWER_M_TILE,
             Planet => *LOWER_P_TILE,
             Space => *EMPTY_TILE,
@@ -25,6 +28,15 @@ impl From<&EntityType> for Rect {
     }
 }
 
+/// Giant stars get a capital letter, standing out from the regular stars around them.
+pub fn star_rect(star_class: StarClass) -> Rect {
+    if star_class.is_giant() {
+        *UPPER_S_TILE
+    } else {
+        *LOWER_S_TILE
+    }
+}
+
 pub fn make_tile_rect(x: u8, y: u8) -> Rect {
     Rect::new(
         x as i32 * TILE_PIXEL_WIDTH as i32,
@@ -43,10 +55,19 @@ pub fn make_multi_tile_rect(x: u8, y: u8, width: u8, height: u8) -> Rect {
     )
 }
 
+/// Whether the tileset has a glyph for the character. Text typed by the player should be filtered
+/// through this before it reaches `rect_from_char`.
+pub fn supports_char(character: char) -> bool {
+    character == ' ' || character.is_ascii_graphic()
+}
+
 pub fn rect_from_char(character: char) -> Rect {
     match character {
         ' ' => *EMPTY_TILE,
         '!' => *EXCLAMATION_POINT_TILE,
+        ',' => make_tile_rect(12, 2),
+        '-' => make_tile_rect(13, 2),
+        '.' => make_tile_rect(14, 2),
         '0' => make_tile_rect(0, 3),
         '1' => make_tile_rect(1, 3),
         '2' => make_tile_rect(2, 3),
@@ -76,7 +97,7 @@ pub fn rect_from_char(character: char) -> Rect {
         'P' => make_tile_rect(0, 5),
         'Q' => make_tile_rect(1, 5),
         'R' => make_tile_rect(2, 5),
-        'S' => make_tile_rect(3, 5),
+        'S' => *UPPER_S_TILE,
         'T' => make_tile_rect(4, 5),
         'U' => make_tile_rect(5, 5),
         'V' => make_tile_rect(6, 5),
@@ -110,6 +131,11 @@ pub fn rect_from_char(character: char) -> Rect {
         'x' => make_tile_rect(8, 7),
         'y' => make_tile_rect(9, 7),
         'z' => make_tile_rect(10, 7),
+        // The tileset follows the code page 437 layout, which matches ASCII for printable
+        // characters.
+        character if character.is_ascii_graphic() => {
+            make_tile_rect(character as u8 % 16, character as u8 / 16)
+        }
         character => panic!("tried to get rect for unsupported character: '{character}'"),
     }
 }
@@ -0,0 +1,30 @@
+use sdl2::render::{Canvas, Texture};
+use sdl2::video::Window;
+
+use crate::colors;
+
+use super::{render_panel, TILE_PIXEL_WIDTH};
+
+/// Draws a small box of text just below and to the right of the mouse, moved inward where it would
+/// otherwise run off the window.
+pub fn render_tooltip(
+    canvas: &mut Canvas<Window>,
+    tiles_texture: &mut Texture<'_>,
+    lines: &[String],
+    mouse_x: i32,
+    mouse_y: i32,
+) {
+    let width = lines.iter().map(String::len).max().unwrap_or(0) + 2;
+    let x = (mouse_x / TILE_PIXEL_WIDTH as i32 + 1).clamp(0, 64 - width as i32);
+    let y = (mouse_y / TILE_PIXEL_WIDTH as i32 + 1).clamp(0, 64 - lines.len() as i32);
+
+    render_panel(
+        canvas,
+        tiles_texture,
+        lines,
+        x as u8,
+        y as u8,
+        width,
+        colors::OVERLAY,
+    );
+}
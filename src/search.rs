@@ -0,0 +1,96 @@
+use crate::entity::EntityId;
+use crate::text_field::TextField;
+use crate::world::World;
+
+/// How many matches the search overlay lists.
+pub const MAX_RESULTS: usize = 8;
+
+/// The overlay is 32 tiles wide, of which the "/ " prompt and the cursor take three.
+const MAX_QUERY_LENGTH: usize = 28;
+
+/// Scores how well `query` fuzzily matches `name`, lower is better. The query matches when its
+/// characters appear in the name in order, ignoring case. Matches starting early and with fewer
+/// characters skipped in between score better. Returns `None` when the query doesn't match.
+fn fuzzy_score(query: &str, name: &str) -> Option<usize> {
+    let mut name_chars = name.chars().map(|c| c.to_ascii_lowercase()).enumerate();
+    let mut score = 0;
+    let mut last_match = None;
+
+    for query_char in query.chars().map(|c| c.to_ascii_lowercase()) {
+        let (index, _) = name_chars.find(|&(_, name_char)| name_char == query_char)?;
+        score += match last_match {
+            Some(last_index) => index - last_index - 1,
+            None => index,
+        };
+        last_match = Some(index);
+    }
+
+    Some(score)
+}
+
+/// An overlay, opened with slash, that finds entities by name.
+pub struct Search {
+    pub open: bool,
+    pub query: TextField,
+    /// The best matches for the query, best first.
+    pub results: Vec<EntityId>,
+    /// Index into `results` of the highlighted match.
+    pub selected: usize,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            open: false,
+            query: TextField::new(MAX_QUERY_LENGTH, '/'),
+            results: vec![],
+            selected: 0,
+        }
+    }
+}
+
+impl Search {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.results.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_entity(&self) -> Option<EntityId> {
+        self.results.get(self.selected).copied()
+    }
+
+    /// Matches the query against the names of all entities, keeping the best matches. Call whenever
+    /// the query changes, the highlight moves back to the best match.
+    pub fn refresh(&mut self, world: &World) {
+        let mut matches: Vec<(usize, &str, EntityId)> = world
+            .names
+            .iter()
+            .filter_map(|(&entity_id, name)| {
+                fuzzy_score(self.query.as_str(), name)
+                    .map(|score| (score, name.as_str(), entity_id))
+            })
+            .collect();
+        matches.sort();
+
+        self.results = matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, _, entity_id)| entity_id)
+            .collect();
+        self.selected = 0;
+    }
+}
@@ -1,15 +1,27 @@
 use crate::entity::{Orbital, OrbitalEntity};
 use crate::location::LocationMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub const SIMULATION_UNIT_DURATION: Duration = Duration::from_millis(100);
 
+/// The most real time we simulate in a single step to catch up. When we're further behind than
+/// this we drop the excess time, otherwise a slow machine would fall further behind with every
+/// step it spends catching up. At higher speeds this covers more simulation units.
+const MAX_CATCH_UP: Duration = Duration::from_secs(1);
+
+/// Simulation speeds the player can cycle through, as multiples of real time.
+const SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+const DEFAULT_SPEED_INDEX: usize = 2;
+
 pub fn update_orbital_entities(
     orbital_entities: &mut Vec<OrbitalEntity>,
     location_map: &mut LocationMap,
 ) {
     for entity in orbital_entities {
-        let anchor_position = location_map.get(&entity.anchor_id).unwrap();
+        // Despawning reparents or removes orbitals, so the anchor should always be there.
+        let Some(anchor_position) = location_map.get(&entity.anchor_id) else {
+            continue;
+        };
         entity.update_position(
             anchor_position.x,
             anchor_position.y,
@@ -19,7 +31,103 @@ pub fn update_orbital_entities(
     }
 }
 
-pub fn run_simulation_loop() {
-    // This function can be expanded to include the main simulation loop logic.
-    // For now, it serves as a placeholder for where the loop logic would go.
+pub struct Step {
+    /// How many simulation units are due.
+    pub simulation_units: u32,
+    /// How far along we are toward the next simulation unit, from 0 up to 1. Rendering uses this to
+    /// interpolate between the previous and current locations.
+    pub alpha: f64,
+    /// Whether we were so far behind that some time was dropped instead of simulated.
+    pub dropped_time: bool,
+}
+
+/// Drives the simulation at a fixed rate of one simulation unit per `SIMULATION_UNIT_DURATION`,
+/// however often the main loop comes around to render. The rate scales with the chosen speed.
+pub struct GameLoop {
+    last_step: Instant,
+    accumulator: Duration,
+    speed_index: usize,
+    /// Paused by the player.
+    paused: bool,
+    /// Paused on the player's behalf, e.g. while the window is unfocused. Kept apart from `paused`
+    /// so resuming doesn't undo a pause the player chose.
+    pub suspended: bool,
+    /// Simulation units requested one at a time while paused, run on the next step.
+    pending_units: u32,
+}
+
+impl GameLoop {
+    pub fn new() -> Self {
+        Self {
+            last_step: Instant::now(),
+            accumulator: Duration::ZERO,
+            speed_index: DEFAULT_SPEED_INDEX,
+            paused: false,
+            suspended: false,
+            pending_units: 0,
+        }
+    }
+
+    /// How many times faster than real time the simulation runs.
+    pub fn speed(&self) -> f64 {
+        SPEEDS[self.speed_index]
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused || self.suspended
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Runs a single simulation unit on the next step. Only has an effect while paused, for
+    /// watching the simulation unit by unit.
+    pub fn advance_one(&mut self) {
+        if self.paused {
+            self.pending_units += 1;
+        }
+    }
+
+    /// Accounts for the real time passed since the last step and reports how many simulation units
+    /// should run now.
+    pub fn step(&mut self) -> Step {
+        let now = Instant::now();
+        if !self.is_paused() {
+            self.accumulator += (now - self.last_step).mul_f64(self.speed());
+        }
+        self.last_step = now;
+
+        let max_catch_up_units = (MAX_CATCH_UP.mul_f64(self.speed()).as_nanos()
+            / SIMULATION_UNIT_DURATION.as_nanos()) as u32;
+        let mut simulation_units = 0;
+        while self.accumulator >= SIMULATION_UNIT_DURATION && simulation_units < max_catch_up_units
+        {
+            self.accumulator -= SIMULATION_UNIT_DURATION;
+            simulation_units += 1;
+        }
+
+        let dropped_time = self.accumulator >= SIMULATION_UNIT_DURATION;
+        if dropped_time {
+            self.accumulator = Duration::from_nanos(
+                (self.accumulator.as_nanos() % SIMULATION_UNIT_DURATION.as_nanos()) as u64,
+            );
+        }
+
+        simulation_units += std::mem::take(&mut self.pending_units);
+
+        Step {
+            simulation_units,
+            alpha: self.accumulator.as_secs_f64() / SIMULATION_UNIT_DURATION.as_secs_f64(),
+            dropped_time,
+        }
+    }
 }
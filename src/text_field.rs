@@ -0,0 +1,76 @@
+use sdl2::clipboard::ClipboardUtil;
+use tracing::warn;
+
+use crate::render;
+
+/// A single line of text the player types or pastes into, shared by the console and the search.
+pub struct TextField {
+    text: String,
+    max_length: usize,
+    /// The key that opens the field also arrives as text input. Its character is never typed.
+    opening_char: char,
+}
+
+impl TextField {
+    pub fn new(max_length: usize, opening_char: char) -> Self {
+        Self {
+            text: String::new(),
+            max_length,
+            opening_char,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Appends typed or pasted text, dropping anything the tileset can't draw.
+    pub fn push_str(&mut self, text: &str) {
+        // Pasted text may span lines or contain tabs, which we flatten into spaces.
+        let chars = text
+            .chars()
+            .map(|character| {
+                if character.is_whitespace() {
+                    ' '
+                } else {
+                    character
+                }
+            })
+            .filter(|&character| {
+                character != self.opening_char && render::supports_char(character)
+            });
+
+        for character in chars {
+            if self.text.len() >= self.max_length {
+                break;
+            }
+            self.text.push(character);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.text.pop();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+    }
+
+    /// Returns the text, leaving the field empty.
+    pub fn take(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn paste(&mut self, clipboard: &ClipboardUtil) {
+        match clipboard.clipboard_text() {
+            Ok(text) => self.push_str(&text),
+            Err(err) => warn!(%err, "failed to paste from clipboard"),
+        }
+    }
+
+    pub fn copy(&self, clipboard: &ClipboardUtil) {
+        if let Err(err) = clipboard.set_clipboard_text(&self.text) {
+            warn!(%err, "failed to copy to clipboard");
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use rand::distributions::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::entity::{EntityId, EntityType, EntityTypeMap, Orbit, OrbitalEntity, StarClass};
+use crate::location::{LocationMap, Point};
+
+pub struct World {
+    pub entities: Vec<EntityId>,
+    pub entity_types: EntityTypeMap,
+    pub locations: LocationMap,
+    pub orbital_entities: Vec<OrbitalEntity>,
+    /// Display names, used to find entities by name. Every entity starts out with a generic name
+    /// made of its type and id.
+    pub names: HashMap<EntityId, String>,
+    /// The spectral class of every star, picked at random when the star spawns.
+    pub star_classes: HashMap<EntityId, StarClass>,
+    /// The seed `rng` started from.
+    pub seed: u64,
+    /// All randomness in the simulation is drawn from here, so two worlds created with the same seed
+    /// and fed the same inputs end up in the same state.
+    pub rng: StdRng,
+    /// How many simulation units the world has been simulated for.
+    pub elapsed_simulation_units: u64,
+    next_entity_id: EntityId,
+}
+
+impl World {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            entities: vec![],
+            entity_types: EntityTypeMap::new(),
+            locations: LocationMap::new(),
+            orbital_entities: vec![],
+            names: HashMap::new(),
+            star_classes: HashMap::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            elapsed_simulation_units: 0,
+            next_entity_id: 0,
+        }
+    }
+
+    pub fn spawn(&mut self, entity_type: EntityType, x: i32, y: i32) -> EntityId {
+        let entity_id = self.next_entity_id;
+        self.next_entity_id += 1;
+
+        self.entities.push(entity_id);
+        self.names
+            .insert(entity_id, format!("{entity_type} {entity_id}"));
+        if matches!(entity_type, EntityType::Star) {
+            let class_distribution = WeightedIndex::new(StarClass::WEIGHTS)
+                .expect("expect star class weights to be valid");
+            let star_class = StarClass::ALL[self.rng.sample(class_distribution)];
+            self.star_classes.insert(entity_id, star_class);
+        }
+        self.entity_types.insert(entity_id, entity_type);
+        self.locations.add_entity(entity_id, x, y);
+
+        entity_id
+    }
+
+    /// Spawns an entity orbiting `anchor_id`, starting at `angle` radians along the orbit. The
+    /// anchor has to exist. As the new entity can't be anyone's anchor yet, this can't create a
+    /// cycle of entities orbiting each other.
+    pub fn spawn_orbital(
+        &mut self,
+        entity_type: EntityType,
+        anchor_id: EntityId,
+        orbit: Orbit,
+        angle: f64,
+        angular_velocity: f64,
+    ) -> Result<EntityId, String> {
+        let anchor = *self
+            .locations
+            .get(&anchor_id)
+            .ok_or_else(|| format!("anchor {anchor_id} does not exist"))?;
+        let (offset_x, offset_y) = orbit.offset(angle);
+        let position = Point {
+            x: anchor.x + offset_x as i32,
+            y: anchor.y + offset_y as i32,
+        };
+
+        let entity_id = self.spawn(entity_type, position.x, position.y);
+        self.orbital_entities.push(OrbitalEntity {
+            id: entity_id,
+            anchor_id,
+            orbit,
+            angle,
+            angular_velocity,
+            position,
+        });
+
+        Ok(entity_id)
+    }
+
+    /// Spawns a ring of asteroids around `anchor_id`. Each asteroid gets a random starting angle, a
+    /// small radial offset and a slightly eccentric orbit so the belt reads as a band rather than a
+    /// perfect circle. Asteroids further out orbit slower, following Kepler's third law.
+    pub fn spawn_asteroid_belt(
+        &mut self,
+        anchor_id: EntityId,
+        radius: f64,
+        count: u32,
+        angular_velocity: f64,
+    ) -> Result<(), String> {
+        for _ in 0..count {
+            let asteroid_radius = radius + self.rng.gen_range(-1.5..=1.5);
+            let asteroid_angle = self.rng.gen_range(0.0..TAU);
+            let asteroid_orbit = Orbit::elliptical(
+                asteroid_radius,
+                self.rng.gen_range(0.0..0.1),
+                self.rng.gen_range(0.0..TAU),
+            );
+            let asteroid_angular_velocity = angular_velocity * (radius / asteroid_radius).powf(1.5);
+            self.spawn_orbital(
+                EntityType::Asteroid,
+                anchor_id,
+                asteroid_orbit,
+                asteroid_angle,
+                asteroid_angular_velocity,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_name(&mut self, entity_id: EntityId, name: impl Into<String>) {
+        self.names.insert(entity_id, name.into());
+    }
+
+    /// Removes an entity from every component collection. Orbitals anchored to the removed entity
+    /// move over to its own anchor, carrying on from where they are. When the removed entity was
+    /// not orbiting anything they have nothing to move over to and stay in place instead.
+    pub fn despawn(&mut self, entity_id: EntityId) {
+        self.entities.retain(|id| *id != entity_id);
+        self.entity_types.remove(&entity_id);
+        self.names.remove(&entity_id);
+        self.star_classes.remove(&entity_id);
+        self.locations.remove_entity(entity_id);
+
+        let removed_orbital = self
+            .orbital_entities
+            .iter()
+            .position(|orbital| orbital.id == entity_id)
+            .map(|index| self.orbital_entities.remove(index));
+        let new_anchor = removed_orbital.and_then(|orbital| {
+            let anchor = *self.locations.get(&orbital.anchor_id)?;
+            Some((orbital.anchor_id, anchor, orbital.angular_velocity))
+        });
+
+        match new_anchor {
+            Some((anchor_id, anchor, angular_velocity)) => {
+                for orphan in self
+                    .orbital_entities
+                    .iter_mut()
+                    .filter(|orbital| orbital.anchor_id == entity_id)
+                {
+                    // A circular orbit through the orphan's current position, so it doesn't jump.
+                    let dx = (orphan.position.x - anchor.x) as f64;
+                    let dy = (orphan.position.y - anchor.y) as f64;
+                    orphan.anchor_id = anchor_id;
+                    orphan.orbit = Orbit::circular(dx.hypot(dy));
+                    orphan.angle = dy.atan2(dx);
+                    orphan.angular_velocity = angular_velocity;
+                }
+            }
+            None => self
+                .orbital_entities
+                .retain(|orbital| orbital.anchor_id != entity_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation;
+
+    fn world_with_belt(seed: u64) -> World {
+        let mut world = World::new(seed);
+        let sol_id = world.spawn(EntityType::Star, 0, 0);
+        world.spawn_asteroid_belt(sol_id, 24.0, 48, 0.05).unwrap();
+        world
+    }
+
+    /// Sol with Earth orbiting it and the Moon orbiting Earth.
+    fn solar_system() -> (World, EntityId, EntityId, EntityId) {
+        let mut world = World::new(42);
+        let sol_id = world.spawn(EntityType::Star, 0, 0);
+        let earth_id = world
+            .spawn_orbital(EntityType::Planet, sol_id, Orbit::circular(16.0), 0.0, 0.1)
+            .unwrap();
+        let moon_id = world
+            .spawn_orbital(EntityType::Moon, earth_id, Orbit::circular(2.0), 0.0, 0.2)
+            .unwrap();
+        (world, sol_id, earth_id, moon_id)
+    }
+
+    fn update(world: &mut World, simulation_units: u32) {
+        for _ in 0..simulation_units {
+            simulation::update_orbital_entities(&mut world.orbital_entities, &mut world.locations);
+        }
+    }
+
+    fn orbital(world: &World, entity_id: EntityId) -> Option<&OrbitalEntity> {
+        world
+            .orbital_entities
+            .iter()
+            .find(|orbital| orbital.id == entity_id)
+    }
+
+    #[test]
+    fn despawning_anchor_reparents_orbitals_to_its_anchor() {
+        let (mut world, sol_id, earth_id, moon_id) = solar_system();
+        update(&mut world, 37);
+        let before = world.locations[&moon_id];
+
+        world.despawn(earth_id);
+        assert_eq!(orbital(&world, moon_id).unwrap().anchor_id, sol_id);
+
+        // One simulation unit moves the moon a fraction of a tile, plus rounding.
+        update(&mut world, 1);
+        let after = world.locations[&moon_id];
+        assert!((after.x - before.x).abs() <= 1 && (after.y - before.y).abs() <= 1);
+    }
+
+    #[test]
+    fn despawning_static_anchor_leaves_orbitals_static() {
+        let (mut world, sol_id, earth_id, moon_id) = solar_system();
+
+        world.despawn(sol_id);
+
+        assert!(orbital(&world, earth_id).is_none());
+        assert!(world.locations.contains_key(&earth_id));
+        assert_eq!(orbital(&world, moon_id).unwrap().anchor_id, earth_id);
+    }
+
+    #[test]
+    fn spawn_orbital_requires_anchor() {
+        let mut world = World::new(42);
+        let result = world.spawn_orbital(EntityType::Planet, 7, Orbit::circular(16.0), 0.0, 0.1);
+        assert!(result.is_err());
+        assert!(world.entities.is_empty());
+    }
+
+    #[test]
+    fn update_after_despawn_keeps_running() {
+        let (mut world, sol_id, earth_id, moon_id) = solar_system();
+
+        world.despawn(earth_id);
+        update(&mut world, 10);
+        world.despawn(sol_id);
+        update(&mut world, 10);
+
+        assert!(world.locations.contains_key(&moon_id));
+    }
+
+    #[test]
+    fn same_seed_gives_same_world() {
+        let mut a = world_with_belt(42);
+        let mut b = world_with_belt(42);
+        let mut c = world_with_belt(43);
+
+        update(&mut a, 10_000);
+        update(&mut b, 10_000);
+        update(&mut c, 10_000);
+
+        assert_eq!(*a.locations, *b.locations);
+        assert_eq!(a.star_classes, b.star_classes);
+        // Without this a world ignoring its seed would pass as well.
+        assert_ne!(*a.locations, *c.locations);
+    }
+}